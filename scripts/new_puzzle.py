@@ -54,7 +54,9 @@ source_file.write_text(template.format(year=args.year, day=args.day))
 # Add the new source file to the mod.rs file
 mod_file = source_directory / "mod.rs"
 mod_file_contents = mod_file.read_text()
-match = re.search(r"crate::puzzle_set\!\(\s*((?:day\d+,?\s*)+)\);", mod_file_contents)
+match = re.search(
+    r"crate::puzzle_set\!\(\s*((?:day\d+,?\s*)+)(?:;[^)]*)?\);", mod_file_contents
+)
 if not match:
     sys.exit(f"ERROR: failed to parse {mod_file}")
 module_match = match.group(1).strip()
@@ -0,0 +1,278 @@
+/*
+** src/automaton.rs
+*/
+
+use crate::cycle::Cycle;
+use crate::types::{Grid, Octile};
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Cells of a grid whose states decide the next state of a cell
+pub enum Neighborhood<T> {
+    /// The four orthogonally adjacent cells
+    #[allow(unused)]
+    VonNeumann,
+    /// The eight adjacent cells, including diagonals
+    Moore,
+    /// The nearest cell in each of the eight directions which cannot be seen past, where the
+    /// function is true for cells which can be
+    LineOfSight(fn(&T) -> bool),
+}
+
+/// Cellular automaton over a grid, where every cell updates at once according to the rule, from
+/// its own state and the states of its neighborhood in the previous generation
+pub struct GridAutomaton<T, F> {
+    pub grid: Grid<T>,
+    // the next generation is written here and then swapped with the grid
+    next: Grid<T>,
+    neighbors: Grid<Vec<(usize, usize)>>,
+    rule: F,
+    pub generation: usize,
+}
+
+impl<T, F> GridAutomaton<T, F>
+where
+    T: Clone + PartialEq,
+    F: Fn(&T, &[&T]) -> T,
+{
+    /// The neighborhood of each cell is found once from the initial grid, so any cells which can
+    /// be seen past must never change state
+    pub fn new(grid: Grid<T>, neighborhood: Neighborhood<T>, rule: F) -> Self {
+        let neighbors = Grid::from_fn(grid.width, grid.height, |i, j| match neighborhood {
+            Neighborhood::VonNeumann => grid.neighbors(i, j),
+            Neighborhood::Moore => grid.neighbors_with_diagonal(i, j),
+            Neighborhood::LineOfSight(see_past) => Octile::all_cases()
+                .filter_map(|direction| {
+                    let mut position = grid.neighbor(i, j, direction)?;
+                    while see_past(&grid[position]) {
+                        position = grid.neighbor(position.0, position.1, direction)?;
+                        // a wrapping grid can lead the line of sight back around to the cell
+                        if position == (i, j) {
+                            return None;
+                        }
+                    }
+                    Some(position)
+                })
+                .collect(),
+        });
+        Self {
+            next: grid.clone(),
+            grid,
+            neighbors,
+            rule,
+            generation: 0,
+        }
+    }
+
+    // writes the generation after cells into next, returning the number of cells which changed
+    fn next_generation(
+        neighbors: &Grid<Vec<(usize, usize)>>,
+        rule: &F,
+        cells: &Grid<T>,
+        next: &mut Grid<T>,
+    ) -> usize {
+        let mut changed = 0;
+        let mut states = Vec::new();
+        for (i, j, cell) in cells.iter_grid() {
+            states.clear();
+            states.extend(neighbors[(i, j)].iter().map(|&position| &cells[position]));
+            let state = rule(cell, &states);
+            if state != *cell {
+                changed += 1;
+            }
+            next[(i, j)] = state;
+        }
+        changed
+    }
+
+    /// Advances one generation, returning the number of cells which changed
+    pub fn step(&mut self) -> usize {
+        let changed =
+            Self::next_generation(&self.neighbors, &self.rule, &self.grid, &mut self.next);
+        std::mem::swap(&mut self.grid, &mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    /// Advances until a generation changes no cells, returning the number of generations which
+    /// changed any
+    pub fn run_until_stable(&mut self) -> usize {
+        let start = self.generation;
+        while self.step() > 0 {}
+        self.generation - start - 1
+    }
+
+    /// Finds the cycle which the grid eventually repeats from its current generation, without
+    /// advancing the automaton
+    #[allow(unused)]
+    pub fn find_cycle(&self) -> Cycle<Grid<T>>
+    where
+        T: Eq + Hash,
+    {
+        Cycle::find(self.grid.clone(), |cells| {
+            let mut next = cells.clone();
+            Self::next_generation(&self.neighbors, &self.rule, cells, &mut next);
+            next
+        })
+    }
+
+    /// Number of cells with a state matching the predicate
+    pub fn count<P>(&self, predicate: P) -> usize
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.grid
+            .iter_grid()
+            .filter(|(_, _, cell)| predicate(cell))
+            .count()
+    }
+}
+
+/// Cellular automaton over an unbounded space which keeps only the live points, where the rule
+/// decides whether a point is alive from whether it was and how many of its neighbors were. Only
+/// live points and their neighbors are considered, so a dead point with no live neighbors stays
+/// dead.
+pub struct SparseAutomaton<P, N, F> {
+    pub alive: HashSet<P>,
+    // the next generation is collected here and then swapped with the live points
+    next: HashSet<P>,
+    live_neighbors: HashMap<P, usize>,
+    neighbors: N,
+    rule: F,
+    pub generation: usize,
+}
+
+impl<P, N, I, F> SparseAutomaton<P, N, F>
+where
+    P: Copy + Eq + Hash,
+    N: Fn(P) -> I,
+    I: IntoIterator<Item = P>,
+    F: Fn(bool, usize) -> bool,
+{
+    pub fn new(alive: HashSet<P>, neighbors: N, rule: F) -> Self {
+        Self {
+            alive,
+            next: HashSet::new(),
+            live_neighbors: HashMap::new(),
+            neighbors,
+            rule,
+            generation: 0,
+        }
+    }
+
+    /// Advances one generation, returning the number of points which changed
+    pub fn step(&mut self) -> usize {
+        self.live_neighbors.clear();
+        for &point in self.alive.iter() {
+            self.live_neighbors.entry(point).or_default();
+            for neighbor in (self.neighbors)(point) {
+                *self.live_neighbors.entry(neighbor).or_default() += 1;
+            }
+        }
+        self.next.clear();
+        self.next.extend(
+            self.live_neighbors
+                .iter()
+                .filter(|&(point, &count)| (self.rule)(self.alive.contains(point), count))
+                .map(|(&point, _)| point),
+        );
+        let changed = self.next.symmetric_difference(&self.alive).count();
+        std::mem::swap(&mut self.alive, &mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    /// Advances the given number of generations
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// Advances until a generation changes no points, returning the number of generations which
+    /// changed any
+    #[allow(unused)]
+    pub fn run_until_stable(&mut self) -> usize {
+        let start = self.generation;
+        while self.step() > 0 {}
+        self.generation - start - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Point;
+
+    fn life(alive: bool, live_neighbors: usize) -> bool {
+        live_neighbors == 3 || (alive && live_neighbors == 2)
+    }
+
+    #[test]
+    fn grid_automaton() {
+        // a blinker, which alternates between a horizontal and vertical line
+        let grid = Grid::parse_with(".....\n.....\n.###.\n.....\n.....", |c| Some(c == '#'));
+        let mut automaton =
+            GridAutomaton::new(grid.unwrap(), Neighborhood::Moore, |&alive, cells| {
+                life(alive, cells.iter().filter(|&&&cell| cell).count())
+            });
+        let cycle = automaton.find_cycle();
+        assert_eq!((cycle.prefix, cycle.length), (0, 2));
+        assert_eq!(automaton.generation, 0);
+        assert_eq!(automaton.step(), 4);
+        assert_eq!(automaton.count(|&cell| cell), 3);
+        assert!(automaton.grid[(1, 2)] && automaton.grid[(3, 2)]);
+
+        // a block is stable from the start
+        let grid = Grid::parse_with("....\n.##.\n.##.\n....", |c| Some(c == '#'));
+        let mut automaton =
+            GridAutomaton::new(grid.unwrap(), Neighborhood::Moore, |&alive, cells| {
+                life(alive, cells.iter().filter(|&&&cell| cell).count())
+            });
+        assert_eq!(automaton.run_until_stable(), 0);
+        assert_eq!(automaton.generation, 1);
+    }
+
+    #[test]
+    fn line_of_sight() {
+        // each cell counts the cells it can see, looking past any zeros
+        let grid = Grid::parse_with("1001\n0000\n1001", |c| c.to_digit(10));
+        let automaton = GridAutomaton::new(
+            grid.unwrap(),
+            Neighborhood::LineOfSight(|&cell| cell == 0),
+            |_, cells| cells.len() as u32,
+        );
+        assert_eq!(automaton.neighbors[(0, 0)], vec![(2, 0), (0, 3)]);
+        assert_eq!(automaton.neighbors[(1, 1)], vec![(0, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn sparse_automaton() {
+        // a glider moves one cell diagonally every 4 generations
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+            .map(|(x, y)| Point::new(x, y))
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let mut automaton = SparseAutomaton::new(
+            glider.clone(),
+            |point: Point| Octile::all_cases().map(move |d| point + d.point_delta()),
+            life,
+        );
+        automaton.run(4);
+        let moved = glider
+            .iter()
+            .map(|&point| point + Point::new(1, 1))
+            .collect::<HashSet<_>>();
+        assert_eq!(automaton.alive, moved);
+
+        // a lone point dies and then nothing changes
+        let mut automaton = SparseAutomaton::new(
+            HashSet::from([Point::new(0, 0)]),
+            |point: Point| Octile::all_cases().map(move |d| point + d.point_delta()),
+            life,
+        );
+        assert_eq!(automaton.run_until_stable(), 1);
+        assert!(automaton.alive.is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+/*
+** src/cycle.rs
+*/
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// States reached by repeatedly stepping from an initial state, which eventually repeat. The
+/// states before the cycle and one full cycle are kept, so the state after any number of steps
+/// can be looked up without simulating them all.
+#[derive(Clone, Debug)]
+pub struct Cycle<S> {
+    /// Number of steps before the first state which is part of the cycle
+    pub prefix: usize,
+    /// Number of steps after which the states repeat
+    pub length: usize,
+    states: Vec<S>,
+}
+
+impl<S> Cycle<S> {
+    /// Finds the cycle by hashing each state until one is seen again
+    pub fn find<F>(initial: S, mut step: F) -> Self
+    where
+        S: Clone + Eq + Hash,
+        F: FnMut(&S) -> S,
+    {
+        let mut seen = HashMap::new();
+        let mut states = Vec::new();
+        let mut state = initial;
+        loop {
+            if let Some(&prefix) = seen.get(&state) {
+                let length = states.len() - prefix;
+                return Self {
+                    prefix,
+                    length,
+                    states,
+                };
+            }
+            let next = step(&state);
+            seen.insert(state.clone(), states.len());
+            states.push(state);
+            state = next;
+        }
+    }
+
+    /// Finds the cycle with Brent's algorithm, which only compares states and keeps none of them
+    /// until the cycle is known, for states which are not hashable or too large to keep many of
+    #[allow(unused)]
+    pub fn find_brent<F>(initial: S, mut step: F) -> Self
+    where
+        S: Clone + Eq,
+        F: FnMut(&S) -> S,
+    {
+        // the tortoise waits at each power of two while the hare searches for it
+        let mut power = 1;
+        let mut length = 1;
+        let mut tortoise = initial.clone();
+        let mut hare = step(&initial);
+        while tortoise != hare {
+            if power == length {
+                tortoise = hare.clone();
+                power *= 2;
+                length = 0;
+            }
+            hare = step(&hare);
+            length += 1;
+        }
+
+        // with the hare a cycle ahead of the tortoise, they first meet where the cycle starts
+        let mut tortoise = initial.clone();
+        let mut hare = initial.clone();
+        for _ in 0..length {
+            hare = step(&hare);
+        }
+        let mut prefix = 0;
+        while tortoise != hare {
+            tortoise = step(&tortoise);
+            hare = step(&hare);
+            prefix += 1;
+        }
+
+        let mut states = vec![initial];
+        for _ in 1..(prefix + length) {
+            states.push(step(states.last().unwrap()));
+        }
+        Self {
+            prefix,
+            length,
+            states,
+        }
+    }
+
+    /// The earliest step with the same state as after n steps
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + ((n - self.prefix) % self.length)
+        }
+    }
+
+    /// State after n steps
+    #[allow(unused)]
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.states[self.equivalent_step(n)]
+    }
+
+    /// Whether the sequence settles on a single state which steps to itself
+    #[allow(unused)]
+    pub fn is_fixed_point(&self) -> bool {
+        self.length == 1
+    }
+
+    /// Every distinct state with the step it is first reached at, from the initial state through
+    /// the end of the first cycle
+    pub fn states(&self) -> impl Iterator<Item = (usize, &S)> {
+        self.states.iter().enumerate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // x -> x^2 + 1 mod 255 from 3 runs 3, 10, 101, 2, 5, 26, 167, 95, 101, ...
+    fn square_plus_one(&x: &u64) -> u64 {
+        (x * x + 1) % 255
+    }
+
+    #[test]
+    fn find_cycle() {
+        let cycle = Cycle::find(3, square_plus_one);
+        assert_eq!((cycle.prefix, cycle.length), (2, 6));
+        assert_eq!(*cycle.state_at(0), 3);
+        assert_eq!(*cycle.state_at(8), 101);
+        assert_eq!(*cycle.state_at(1_000_000_000), 5);
+        assert_eq!(cycle.equivalent_step(1_000_000_000), 4);
+        assert_eq!(cycle.states().count(), 8);
+        assert!(!cycle.is_fixed_point());
+
+        let brent = Cycle::find_brent(3, square_plus_one);
+        assert_eq!((brent.prefix, brent.length), (2, 6));
+        assert_eq!(*brent.state_at(1_000_000_000), 5);
+
+        // halving settles on zero
+        let fixed = Cycle::find_brent(100, |&x: &u64| x / 2);
+        assert_eq!((fixed.prefix, fixed.length), (7, 1));
+        assert!(fixed.is_fixed_point());
+        assert_eq!(*fixed.state_at(50), 0);
+        assert_eq!(*Cycle::find(0, |&x: &u64| x).state_at(3), 0);
+    }
+}
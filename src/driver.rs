@@ -2,11 +2,14 @@
 ** src/driver.rs
 */
 
+use crate::image;
 use crate::itertools::*;
-use crate::puzzles::{Answer, Puzzle, PuzzleIterator, PuzzleModules};
+use crate::puzzles::{Answer, Puzzle, PuzzleIterator, PuzzleModules, Solution};
+use crate::random::Rng;
 use crate::utils;
+use crate::visualize;
 
-use log::{debug, info};
+use log::{debug, info, warn};
 
 use std::collections::HashMap;
 use std::env;
@@ -14,6 +17,11 @@ use std::path::Path;
 use std::time::Instant;
 
 const PROJECT_DIR: &str = env!("CARGO_MANIFEST_DIR");
+// generated inputs used when cross-checking against reference solutions
+const CROSS_CHECK_SIZES: [usize; 3] = [4, 16, 64];
+const CROSS_CHECK_SEEDS: u64 = 5;
+// width of the bars when plotting benchmark times against input size
+const PLOT_WIDTH: usize = 40;
 
 fn load_input(puzzle: Puzzle, sample: bool) -> String {
     let prefix = if sample { "sample" } else { "full" };
@@ -51,14 +59,73 @@ pub fn run_puzzles(year: Option<usize>, day: Option<usize>, sample: bool) {
         let t = Instant::now();
         let solution = (solver)(input);
         print_benchmark(t);
+        visualize::finish();
+        image::finish();
         info!("Part A solution: {}", answer_to_string(solution.part_a));
         info!("Part B solution: {}", answer_to_string(solution.part_b));
     }
 }
 
+fn cross_check_input(
+    solver: fn(String) -> Solution,
+    reference: fn(String) -> Solution,
+    input: String,
+    label: &str,
+) -> usize {
+    let expected = (reference)(input.clone());
+    let actual = (solver)(input);
+
+    let mut disagreements = 0;
+    let parts = [
+        ("A", expected.part_a, actual.part_a),
+        ("B", expected.part_b, actual.part_b),
+    ];
+    for (part, expected, actual) in parts {
+        let expected = answer_to_string(expected);
+        let actual = answer_to_string(actual);
+        if expected == actual {
+            info!("Part {} ({} input): {}", part, label, actual);
+        } else {
+            warn!(
+                "Part {} ({} input): MISMATCH: reference: {}: solution: {}",
+                part, label, expected, actual
+            );
+            disagreements += 1;
+        }
+    }
+    disagreements
+}
+
+pub fn run_cross_check(year: Option<usize>, day: Option<usize>, seed: u64) {
+    let mut disagreements = 0;
+    for puzzle in PuzzleIterator::new(year, day) {
+        let Some(reference) = PuzzleModules::reference(puzzle.year, puzzle.day) else {
+            continue;
+        };
+        let solver = PuzzleModules::dispatch(puzzle.year, puzzle.day);
+        info!("{}", puzzle);
+
+        for sample in [true, false] {
+            let label = if sample { "sample" } else { "full" };
+            let input = load_input(puzzle, sample);
+            disagreements += cross_check_input(solver, reference, input, label);
+        }
+        if let Some(generator) = PuzzleModules::generator(puzzle.year, puzzle.day) {
+            for size in CROSS_CHECK_SIZES {
+                for seed in seed..(seed + CROSS_CHECK_SEEDS) {
+                    let input = generator(&mut Rng::new(seed), size);
+                    let label = format!("generated size={} seed={}", size, seed);
+                    disagreements += cross_check_input(solver, reference, input, &label);
+                }
+            }
+        }
+    }
+    info!("Cross-check found {} disagreement(s)", disagreements);
+}
+
 pub fn run_benchmark(iterations: usize) {
     // TODO: remove when solutions are no longer incomplete
-    let incomplete = vec![2020, 2021, 2022, 2023];
+    let incomplete = [2020, 2021, 2022, 2023];
 
     // Run puzzle benchmarks
     let mut benchmark = HashMap::new();
@@ -100,3 +167,56 @@ pub fn run_benchmark(iterations: usize) {
         println!();
     }
 }
+
+pub fn run_scale_benchmark(
+    year: Option<usize>,
+    day: Option<usize>,
+    sizes: &[usize],
+    iterations: usize,
+    seed: u64,
+) {
+    println!("## Scaling\n");
+    println!(
+        "Results are averaged over {} generated inputs of each size.\n",
+        iterations
+    );
+    for puzzle in PuzzleIterator::new(year, day) {
+        let Some(generator) = PuzzleModules::generator(puzzle.year, puzzle.day) else {
+            continue;
+        };
+        let solver = PuzzleModules::dispatch(puzzle.year, puzzle.day);
+
+        let mut times = Vec::with_capacity(sizes.len());
+        for &size in sizes {
+            let mut total = 0.0;
+            for i in 0..iterations {
+                let input = generator(&mut Rng::new(seed + i as u64), size);
+                let t = Instant::now();
+                (solver)(input);
+                total += t.elapsed().as_secs_f64() * 1000.0;
+            }
+            times.push(total / iterations as f64);
+        }
+
+        // Plot the times as a bar chart against the input size
+        println!("### {}\n", puzzle);
+        println!("| Size | Time (ms) | |");
+        println!("|---:|---:|:---|");
+        let max_time = times.iter().copied().fold(f64::MIN_POSITIVE, f64::max);
+        for (size, t) in sizes.iter().zip(times.iter()) {
+            let bar = ((t / max_time) * PLOT_WIDTH as f64).round() as usize;
+            println!("| {} | {:.3} | {} |", size, t, "#".repeat(bar));
+        }
+        // Estimate the growth rate from the smallest and largest inputs
+        if let ([first_size, .., last_size], [first_time, .., last_time]) =
+            (sizes, times.as_slice())
+            && *first_time > 0.0
+            && first_size != last_size
+        {
+            let exponent =
+                (last_time / first_time).ln() / (*last_size as f64 / *first_size as f64).ln();
+            println!("\nEstimated growth: O(n^{:.2})", exponent);
+        }
+        println!();
+    }
+}
@@ -0,0 +1,232 @@
+/*
+** src/geometry.rs
+*/
+
+use crate::types::Point;
+use crate::utils;
+
+/// Where a point lies relative to a polygon
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// Edges of a polygon as pairs of consecutive vertices, including the edge which closes it
+fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+/// Twice the signed area of a polygon using the shoelace formula, which is positive when the
+/// vertices run counter-clockwise with the y-axis pointing up
+pub fn double_signed_area(vertices: &[Point]) -> i64 {
+    edges(vertices)
+        .map(|(a, b)| (a.x * b.y) - (b.x * a.y))
+        .sum()
+}
+
+/// Number of lattice points on the edges of a polygon
+pub fn boundary_points(vertices: &[Point]) -> i64 {
+    edges(vertices)
+        .map(|(a, b)| utils::gcd((b.x - a.x).unsigned_abs(), (b.y - a.y).unsigned_abs()) as i64)
+        .sum()
+}
+
+/// Number of lattice points strictly inside a polygon with lattice vertices, using Pick's theorem
+pub fn interior_points(vertices: &[Point]) -> i64 {
+    // A = i + b/2 - 1, so i = (2A - b + 2) / 2
+    (double_signed_area(vertices).abs() - boundary_points(vertices) + 2) / 2
+}
+
+/// Locates a point relative to a polygon by casting a ray from it along the x-axis and counting
+/// the edges it crosses
+pub fn locate(vertices: &[Point], p: Point) -> Location {
+    locate_scaled(vertices, p, 1)
+}
+
+/// Locates a point relative to the polygon with its vertices multiplied by scale, which allows
+/// points between lattice points to be located exactly
+fn locate_scaled(vertices: &[Point], p: Point, scale: i64) -> Location {
+    let mut inside = false;
+    for (a, b) in edges(vertices) {
+        let a = Point::new(a.x * scale, a.y * scale);
+        let b = Point::new(b.x * scale, b.y * scale);
+        let (x_min, x_max) = utils::min_max(a.x, b.x);
+        let (y_min, y_max) = utils::min_max(a.y, b.y);
+        // each vertex counts as part of the edge above it only, so a ray through a vertex crosses
+        // the boundary once or not at all
+        let spans_ray = (a.y <= p.y) != (b.y <= p.y);
+        if a.x == b.x {
+            // rectilinear polygons only need these comparisons
+            if p.x == a.x && (y_min..=y_max).contains(&p.y) {
+                return Location::Boundary;
+            }
+            if spans_ray && p.x < a.x {
+                inside = !inside;
+            }
+        } else if a.y == b.y {
+            // the ray runs along a horizontal edge rather than crossing it
+            if p.y == a.y && (x_min..=x_max).contains(&p.x) {
+                return Location::Boundary;
+            }
+        } else {
+            // the sign of the cross product gives the side of the edge the point is on
+            let cross = (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x);
+            if cross == 0 && (x_min..=x_max).contains(&p.x) && (y_min..=y_max).contains(&p.y) {
+                return Location::Boundary;
+            }
+            if spans_ray && (cross > 0) == (b.y > a.y) {
+                inside = !inside;
+            }
+        }
+    }
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+/// Doubles of min, max and the coordinates between them, along with the points halfway between
+/// each of those
+fn doubled_steps(min: i64, max: i64, coords: impl Iterator<Item = i64>) -> Vec<i64> {
+    let mut steps = vec![min, max];
+    steps.extend(coords.filter(|c| (min..max).contains(c)));
+    steps.sort();
+    steps.dedup();
+    let mut doubled = vec![steps[0] * 2];
+    for pair in steps.windows(2) {
+        doubled.extend([pair[0] + pair[1], pair[1] * 2]);
+    }
+    doubled
+}
+
+/// Rectilinear polygon prepared for testing many rectangles against it, with the bounding box of
+/// each edge found once up front
+pub struct RectilinearPolygon<'a> {
+    vertices: &'a [Point],
+    // lower and upper corners of each edge
+    edge_bounds: Vec<(Point, Point)>,
+}
+
+impl<'a> RectilinearPolygon<'a> {
+    pub fn new(vertices: &'a [Point]) -> Self {
+        let edge_bounds = edges(vertices)
+            .map(|(p, q)| {
+                let (x_min, x_max) = utils::min_max(p.x, q.x);
+                let (y_min, y_max) = utils::min_max(p.y, q.y);
+                (Point::new(x_min, y_min), Point::new(x_max, y_max))
+            })
+            .collect();
+        Self {
+            vertices,
+            edge_bounds,
+        }
+    }
+
+    /// Whether the axis-aligned rectangle with opposite corners a and b lies within the polygon,
+    /// where the boundary counts as within it
+    pub fn contains_rectangle(&self, a: Point, b: Point) -> bool {
+        let (x_min, x_max) = utils::min_max(a.x, b.x);
+        let (y_min, y_max) = utils::min_max(a.y, b.y);
+        // an edge passing through the inside of the rectangle would leave part of it outside
+        let crossed = self
+            .edge_bounds
+            .iter()
+            .any(|(low, high)| low.x < x_max && high.x > x_min && low.y < y_max && high.y > y_min);
+        if crossed {
+            return false;
+        }
+        // otherwise the inside of the rectangle is either all within the polygon or all outside,
+        // so locating its center decides it
+        let vertices = self.vertices;
+        if x_min < x_max && y_min < y_max {
+            let center = Point::new(x_min + x_max, y_min + y_max);
+            return locate_scaled(vertices, center, 2) != Location::Outside;
+        }
+        // a rectangle with no inside is a line, where the boundary can only change at the
+        // coordinates of a vertex, so locate those and a point between each
+        let xs = doubled_steps(x_min, x_max, vertices.iter().map(|v| v.x));
+        let ys = doubled_steps(y_min, y_max, vertices.iter().map(|v| v.y));
+        xs.iter().all(|&x| {
+            ys.iter()
+                .all(|&y| locate_scaled(vertices, Point::new(x, y), 2) != Location::Outside)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(i64, i64)]) -> Vec<Point> {
+        points.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    #[test]
+    fn area() {
+        // an L shape with a 4x4 square missing its top right 2x2 corner
+        let l_shape = polygon(&[(0, 0), (4, 0), (4, 2), (2, 2), (2, 4), (0, 4)]);
+        assert_eq!(double_signed_area(&l_shape), 24);
+        let reversed = l_shape.iter().rev().copied().collect::<Vec<_>>();
+        assert_eq!(double_signed_area(&reversed), -24);
+        assert_eq!(boundary_points(&l_shape), 16);
+        assert_eq!(interior_points(&l_shape), 5);
+
+        let triangle = polygon(&[(0, 0), (4, 0), (0, 4)]);
+        assert_eq!(boundary_points(&triangle), 12);
+        assert_eq!(interior_points(&triangle), 3);
+    }
+
+    #[test]
+    fn locate_points() {
+        let l_shape = polygon(&[(0, 0), (4, 0), (4, 2), (2, 2), (2, 4), (0, 4)]);
+        assert_eq!(locate(&l_shape, Point::new(1, 1)), Location::Inside);
+        assert_eq!(locate(&l_shape, Point::new(1, 3)), Location::Inside);
+        assert_eq!(locate(&l_shape, Point::new(3, 3)), Location::Outside);
+        assert_eq!(locate(&l_shape, Point::new(3, 2)), Location::Boundary);
+        assert_eq!(locate(&l_shape, Point::new(0, 4)), Location::Boundary);
+        // rays passing through vertices
+        assert_eq!(locate(&l_shape, Point::new(-1, 2)), Location::Outside);
+        assert_eq!(locate(&l_shape, Point::new(1, 2)), Location::Inside);
+        assert_eq!(locate(&l_shape, Point::new(-1, 0)), Location::Outside);
+
+        let diamond = polygon(&[(0, -2), (2, 0), (0, 2), (-2, 0)]);
+        assert_eq!(locate(&diamond, Point::new(0, 0)), Location::Inside);
+        assert_eq!(locate(&diamond, Point::new(1, 1)), Location::Boundary);
+        assert_eq!(locate(&diamond, Point::new(2, 1)), Location::Outside);
+        assert_eq!(locate(&diamond, Point::new(-3, 0)), Location::Outside);
+    }
+
+    #[test]
+    fn rectangles() {
+        // a U shape with a notch from (2, 2) up to (4, 6)
+        let u_shape = polygon(&[
+            (0, 0),
+            (6, 0),
+            (6, 6),
+            (4, 6),
+            (4, 2),
+            (2, 2),
+            (2, 6),
+            (0, 6),
+        ]);
+        let u_shape = RectilinearPolygon::new(&u_shape);
+        let contains = |a, b, c, d| u_shape.contains_rectangle(Point::new(a, b), Point::new(c, d));
+        assert!(contains(0, 0, 6, 2));
+        assert!(contains(0, 0, 2, 6));
+        assert!(!contains(0, 0, 6, 3));
+        // the notch is outside, even though all its corners are on the boundary
+        assert!(!contains(2, 2, 4, 6));
+        // lines and points
+        assert!(contains(0, 2, 6, 2));
+        assert!(!contains(0, 4, 6, 4));
+        assert!(contains(2, 2, 2, 6));
+        assert!(contains(3, 1, 3, 1));
+        assert!(!contains(3, 4, 3, 4));
+    }
+}
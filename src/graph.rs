@@ -0,0 +1,569 @@
+/*
+** src/graph.rs
+*/
+
+use crate::search::{Cost, Search};
+use crate::types::{DisjointSet, Graph};
+
+use std::cmp;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::ops::Sub;
+
+/// Cycle found in a graph which was expected to be acyclic, as the ids of its nodes in the order
+/// they are visited; the last node has an edge back to the first
+#[derive(Debug, PartialEq)]
+pub struct Cycle(pub Vec<usize>);
+
+impl std::fmt::Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "graph contains a cycle of {} nodes", self.0.len())
+    }
+}
+
+impl std::error::Error for Cycle {}
+
+/// Orders the nodes so that every edge leads from an earlier node to a later one, or finds a
+/// cycle if there is no such order
+pub fn topological_sort<N, E>(graph: &Graph<N, E>) -> Result<Vec<usize>, Cycle> {
+    // repeatedly take the nodes which have no incoming edges from the nodes left
+    let mut in_degrees = graph
+        .ids()
+        .map(|id| graph.in_degree(id))
+        .collect::<Vec<_>>();
+    let mut queue = graph
+        .ids()
+        .filter(|&id| in_degrees[id] == 0)
+        .collect::<VecDeque<_>>();
+    let mut order = Vec::with_capacity(graph.len());
+    while let Some(id) = queue.pop_front() {
+        order.push(id);
+        for next in graph.neighbors(id) {
+            in_degrees[next] -= 1;
+            if in_degrees[next] == 0 {
+                queue.push_back(next);
+            }
+        }
+    }
+    if order.len() == graph.len() {
+        Ok(order)
+    } else {
+        Err(find_cycle(graph, &in_degrees))
+    }
+}
+
+fn find_cycle<N, E>(graph: &Graph<N, E>, in_degrees: &[usize]) -> Cycle {
+    // every node left unsorted has an incoming edge from another unsorted node, so walking
+    // backwards along those edges must eventually repeat a node
+    let mut current = graph.ids().find(|&id| in_degrees[id] > 0).unwrap();
+    let mut positions = HashMap::new();
+    let mut walk = Vec::new();
+    while !positions.contains_key(&current) {
+        positions.insert(current, walk.len());
+        walk.push(current);
+        current = graph
+            .incoming(current)
+            .find(|&previous| in_degrees[previous] > 0)
+            .unwrap();
+    }
+    let mut cycle = walk.split_off(positions[&current]);
+    cycle.reverse();
+    Cycle(cycle)
+}
+
+// state of Tarjan's algorithm, shared between the nodes being visited
+struct Tarjan {
+    index: Vec<Option<usize>>,
+    lowlink: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next_index: usize,
+}
+
+impl Tarjan {
+    fn visit(&mut self, node: usize) {
+        self.index[node] = Some(self.next_index);
+        self.lowlink[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+    }
+}
+
+/// Strongly connected components using Tarjan's algorithm, as lists of node ids; a component is
+/// listed before any component with an edge into it
+#[allow(unused)]
+pub fn strongly_connected_components<N, E>(graph: &Graph<N, E>) -> Vec<Vec<usize>> {
+    let mut tarjan = Tarjan {
+        index: vec![None; graph.len()],
+        lowlink: vec![0; graph.len()],
+        on_stack: vec![false; graph.len()],
+        stack: Vec::new(),
+        next_index: 0,
+    };
+    let mut components = Vec::new();
+    for root in graph.ids() {
+        if tarjan.index[root].is_some() {
+            continue;
+        }
+        // depth-first search without recursion, where each frame holds a node and the neighbors
+        // it has yet to follow
+        tarjan.visit(root);
+        let mut frames = vec![(root, graph.neighbors(root).collect::<Vec<_>>())];
+        while let Some((node, pending)) = frames.last_mut() {
+            let node = *node;
+            if let Some(next) = pending.pop() {
+                match tarjan.index[next] {
+                    None => {
+                        tarjan.visit(next);
+                        frames.push((next, graph.neighbors(next).collect()));
+                    }
+                    Some(index) if tarjan.on_stack[next] => {
+                        tarjan.lowlink[node] = cmp::min(tarjan.lowlink[node], index);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                tarjan.lowlink[parent] = cmp::min(tarjan.lowlink[parent], tarjan.lowlink[node]);
+            }
+            // the node is the root of a component, which is everything above it on the stack
+            if Some(tarjan.lowlink[node]) == tarjan.index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = tarjan.stack.pop() {
+                    tarjan.on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+fn bron_kerbosch(
+    neighbors: &[HashSet<usize>],
+    r: Vec<usize>,
+    mut p: BTreeSet<usize>,
+    mut x: BTreeSet<usize>,
+    cliques: &mut Vec<Vec<usize>>,
+) {
+    if p.is_empty() && x.is_empty() {
+        cliques.push(r);
+        return;
+    }
+    // any maximal clique contains the pivot or one of its non-neighbors, so only those need to be
+    // tried; pivoting on the best-connected node leaves the fewest
+    let pivot = p
+        .union(&x)
+        .copied()
+        .max_by_key(|&u| p.iter().filter(|v| neighbors[u].contains(v)).count())
+        .unwrap();
+    let candidates = p
+        .iter()
+        .copied()
+        .filter(|v| !neighbors[pivot].contains(v))
+        .collect::<Vec<_>>();
+    for v in candidates {
+        let mut next_r = r.clone();
+        next_r.push(v);
+        let next_p = p.iter().copied().filter(|u| neighbors[v].contains(u));
+        let next_x = x.iter().copied().filter(|u| neighbors[v].contains(u));
+        bron_kerbosch(
+            neighbors,
+            next_r,
+            next_p.collect(),
+            next_x.collect(),
+            cliques,
+        );
+        p.remove(&v);
+        x.insert(v);
+    }
+}
+
+/// Every maximal clique of an undirected graph, i.e. every set of mutually connected nodes which
+/// no other node is connected to all of, using the Bron-Kerbosch algorithm with pivoting; the
+/// nodes of each clique are sorted by id
+pub fn maximal_cliques<N, E>(graph: &Graph<N, E>) -> Vec<Vec<usize>> {
+    let neighbors = graph
+        .ids()
+        .map(|id| graph.neighbors(id).filter(|&next| next != id).collect())
+        .collect::<Vec<_>>();
+    let mut cliques = Vec::new();
+    let all = graph.ids().collect();
+    bron_kerbosch(&neighbors, Vec::new(), all, BTreeSet::new(), &mut cliques);
+    for clique in cliques.iter_mut() {
+        clique.sort();
+    }
+    cliques
+}
+
+/// The largest clique of an undirected graph, with its nodes sorted by id
+pub fn maximum_clique<N, E>(graph: &Graph<N, E>) -> Vec<usize> {
+    maximal_cliques(graph)
+        .into_iter()
+        .max_by_key(|clique| clique.len())
+        .unwrap_or_default()
+}
+
+/// Shortest distances between every pair of nodes in a graph
+pub struct AllPairs<C> {
+    len: usize,
+    distances: Vec<Option<C>>,
+}
+
+impl<C: Copy> AllPairs<C> {
+    fn new(len: usize) -> Self {
+        Self {
+            len,
+            distances: vec![None; len * len],
+        }
+    }
+
+    /// Shortest distance from one node to another, or None if there is no path
+    pub fn get(&self, from: usize, to: usize) -> Option<C> {
+        self.distances[from * self.len + to]
+    }
+
+    fn set(&mut self, from: usize, to: usize, distance: C) {
+        self.distances[from * self.len + to] = Some(distance);
+    }
+}
+
+/// Shortest distances between every pair of nodes using the Floyd-Warshall algorithm, where the
+/// cost of each edge is given by weight; negative weights are allowed as long as they do not form
+/// a negative cycle
+pub fn floyd_warshall<N, E, C, F>(graph: &Graph<N, E>, weight: F) -> AllPairs<C>
+where
+    C: Cost,
+    F: Fn(&E) -> C,
+{
+    let n = graph.len();
+    let mut all_pairs = AllPairs::new(n);
+    for from in graph.ids() {
+        all_pairs.set(from, from, C::default());
+        for (to, edge) in graph.edges(from) {
+            let cost = weight(edge);
+            if all_pairs.get(from, to).is_none_or(|current| cost < current) {
+                all_pairs.set(from, to, cost);
+            }
+        }
+    }
+    for k in 0..n {
+        for i in 0..n {
+            let Some(ik) = all_pairs.get(i, k) else {
+                continue;
+            };
+            for j in 0..n {
+                if let Some(kj) = all_pairs.get(k, j)
+                    && all_pairs.get(i, j).is_none_or(|ij| ik + kj < ij)
+                {
+                    all_pairs.set(i, j, ik + kj);
+                }
+            }
+        }
+    }
+    all_pairs
+}
+
+/// Shortest distances between every pair of nodes using Johnson's algorithm, which suits sparse
+/// graphs with negative weights; None if the weights form a negative cycle
+#[allow(unused)]
+pub fn johnson<N, E, C, F>(graph: &Graph<N, E>, weight: F) -> Option<AllPairs<C>>
+where
+    C: Cost + Sub<Output = C>,
+    F: Fn(&E) -> C,
+{
+    // find a potential for each node with Bellman-Ford, as if from an extra node with an edge of
+    // weight zero to every other node; if it still changes after every node has been relaxed
+    // then there is a negative cycle
+    let mut potentials = vec![C::default(); graph.len()];
+    let mut relaxed = true;
+    for _ in 0..=graph.len() {
+        if !relaxed {
+            break;
+        }
+        relaxed = false;
+        for from in graph.ids() {
+            for (to, edge) in graph.edges(from) {
+                let potential = potentials[from] + weight(edge);
+                if potential < potentials[to] {
+                    potentials[to] = potential;
+                    relaxed = true;
+                }
+            }
+        }
+    }
+    if relaxed {
+        return None;
+    }
+
+    // reweighting by the potentials makes every edge non-negative without changing which paths
+    // are shortest, so Dijkstra's algorithm can be run from every node
+    let mut all_pairs = AllPairs::new(graph.len());
+    for source in graph.ids() {
+        let visit = Search::new().start(source).iter(|&from| {
+            graph
+                .edges(from)
+                .map(|(to, edge)| (to, weight(edge) + potentials[from] - potentials[to]))
+                .collect::<Vec<_>>()
+        });
+        for (to, distance) in visit {
+            all_pairs.set(source, to, distance + potentials[to] - potentials[source]);
+        }
+    }
+    Some(all_pairs)
+}
+
+/// Number of distinct paths from one node to another, or None if a cycle can be reached from the
+/// start without first passing through the end
+pub fn count_paths<N, E>(graph: &Graph<N, E>, from: usize, to: usize) -> Option<usize> {
+    // the paths from each node are counted once all of its neighbors have been counted, and the
+    // search stops at the end since paths do not continue past it
+    let pending = |node: usize| -> Vec<usize> {
+        if node == to {
+            Vec::new()
+        } else {
+            graph.neighbors(node).collect()
+        }
+    };
+    let mut counts = vec![None; graph.len()];
+    let mut active = vec![false; graph.len()];
+    active[from] = true;
+    let mut frames = vec![(from, pending(from))];
+    while let Some((node, next_nodes)) = frames.last_mut() {
+        let node = *node;
+        if let Some(next) = next_nodes.pop() {
+            if active[next] {
+                return None;
+            }
+            if counts[next].is_none() {
+                active[next] = true;
+                frames.push((next, pending(next)));
+            }
+            continue;
+        }
+        frames.pop();
+        active[node] = false;
+        counts[node] = Some(if node == to {
+            1
+        } else {
+            graph
+                .neighbors(node)
+                .map(|next| counts[next].unwrap())
+                .sum()
+        });
+    }
+    counts[from]
+}
+
+/// Edges of a minimum spanning forest of an undirected graph using Kruskal's algorithm, as the
+/// nodes at either end of each edge and its weight
+#[allow(unused)]
+pub fn minimum_spanning_tree<N, E, C, F>(graph: &Graph<N, E>, weight: F) -> Vec<(usize, usize, C)>
+where
+    C: Ord,
+    F: Fn(&E) -> C,
+{
+    let mut edges = graph
+        .ids()
+        .flat_map(|from| graph.edges(from).map(move |(to, edge)| (from, to, edge)))
+        .filter(|&(from, to, _)| from < to)
+        .map(|(from, to, edge)| (from, to, weight(edge)))
+        .collect::<Vec<_>>();
+    edges.sort_by(|a, b| a.2.cmp(&b.2));
+    DisjointSet::new(graph.len())
+        .spanning_edges(edges)
+        .collect()
+}
+
+/// Minimum cut of a connected undirected graph using the Stoer-Wagner algorithm: the lowest total
+/// weight of edges which must be removed to split the graph in two, along with the nodes on one
+/// side of the split; None if the graph has fewer than two nodes
+#[allow(unused)]
+pub fn minimum_cut<N, E, F>(graph: &Graph<N, E>, weight: F) -> Option<(u64, Vec<usize>)>
+where
+    F: Fn(&E) -> u64,
+{
+    let n = graph.len();
+    if n < 2 {
+        return None;
+    }
+    let mut weights = vec![vec![0; n]; n];
+    for from in graph.ids() {
+        for (to, edge) in graph.edges(from) {
+            if from != to {
+                weights[from][to] += weight(edge);
+            }
+        }
+    }
+
+    // each phase finds the cut between the last two nodes of a maximum adjacency ordering, then
+    // merges them; the nodes merged into each remaining node are kept so the cut can be reported
+    let mut merged = (0..n).map(|id| vec![id]).collect::<Vec<_>>();
+    let mut remaining = (0..n).collect::<Vec<_>>();
+    let mut best: Option<(u64, Vec<usize>)> = None;
+    while remaining.len() > 1 {
+        let mut connection = vec![0; n];
+        let mut added = vec![false; n];
+        let mut order = Vec::with_capacity(remaining.len());
+        for _ in 0..remaining.len() {
+            let next = remaining
+                .iter()
+                .copied()
+                .filter(|&node| !added[node])
+                .max_by_key(|&node| connection[node])
+                .unwrap();
+            added[next] = true;
+            order.push(next);
+            for &node in remaining.iter() {
+                if !added[node] {
+                    connection[node] += weights[next][node];
+                }
+            }
+        }
+
+        let last = order[order.len() - 1];
+        let second_last = order[order.len() - 2];
+        if best.as_ref().is_none_or(|(cut, _)| connection[last] < *cut) {
+            best = Some((connection[last], merged[last].clone()));
+        }
+        let nodes = std::mem::take(&mut merged[last]);
+        merged[second_last].extend(nodes);
+        for &node in remaining.iter() {
+            if node != second_last && node != last {
+                weights[second_last][node] += weights[last][node];
+                weights[node][second_last] = weights[second_last][node];
+            }
+        }
+        remaining.retain(|&node| node != last);
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directed(input: &str) -> Graph<(), ()> {
+        Graph::parse_adjacency(input).unwrap()
+    }
+
+    fn labels<N, E>(graph: &Graph<N, E>, ids: &[usize]) -> Vec<String> {
+        ids.iter().map(|&id| graph.label(id).to_string()).collect()
+    }
+
+    #[test]
+    fn topological_order() {
+        let g = directed("a: b c\nb: d\nc: d\nd:");
+        let order = topological_sort(&g).unwrap();
+        assert_eq!(labels(&g, &order), ["a", "b", "c", "d"]);
+
+        let g = directed("a: b\nb: c\nc: d b\nd:");
+        let Cycle(cycle) = topological_sort(&g).unwrap_err();
+        assert_eq!(cycle.len(), 2);
+        for (k, &node) in cycle.iter().enumerate() {
+            assert!(g.has_edge(node, cycle[(k + 1) % cycle.len()]));
+        }
+    }
+
+    #[test]
+    fn components() {
+        let g = directed("a: b\nb: c\nc: a d\nd: e\ne: d\nf: e");
+        let mut components = strongly_connected_components(&g)
+            .into_iter()
+            .map(|component| {
+                let mut names = labels(&g, &component);
+                names.sort();
+                names.join("")
+            })
+            .collect::<Vec<_>>();
+        // the sink component is found first
+        assert_eq!(components[0], "de");
+        components.sort();
+        assert_eq!(components, ["abc", "de", "f"]);
+    }
+
+    #[test]
+    fn cliques() {
+        let g = Graph::<(), ()>::parse_edges("a-b\nb-c\na-c\nc-d\nd-e\nc-e", "-").unwrap();
+        let mut cliques = maximal_cliques(&g)
+            .iter()
+            .map(|clique| labels(&g, clique).join(""))
+            .collect::<Vec<_>>();
+        cliques.sort();
+        assert_eq!(cliques, ["abc", "cde"]);
+        assert_eq!(maximum_clique(&g).len(), 3);
+    }
+
+    #[test]
+    fn all_pairs() {
+        let mut g = Graph::<(), i64>::new();
+        let ids = ["a", "b", "c", "d"].map(|label| g.intern(label));
+        g.add_edge(ids[0], ids[1], 4);
+        g.add_edge(ids[0], ids[2], 1);
+        g.add_edge(ids[2], ids[1], 2);
+        g.add_edge(ids[1], ids[3], -3);
+        let fw = floyd_warshall(&g, |&w| w);
+        let reweighted = johnson(&g, |&w| w).unwrap();
+        for from in g.ids() {
+            for to in g.ids() {
+                assert_eq!(fw.get(from, to), reweighted.get(from, to));
+            }
+        }
+        assert_eq!(fw.get(ids[0], ids[3]), Some(0));
+        assert_eq!(fw.get(ids[3], ids[0]), None);
+        g.add_edge(ids[3], ids[2], 0);
+        assert!(johnson(&g, |&w| w).is_none());
+    }
+
+    #[test]
+    fn path_counts() {
+        let g = directed("you: a b\na: c out\nb: c\nc: out\nout: x\nx: out");
+        let id = |label| g.id(label).unwrap();
+        assert_eq!(count_paths(&g, id("you"), id("out")), Some(3));
+        assert_eq!(count_paths(&g, id("b"), id("out")), Some(1));
+        assert_eq!(count_paths(&g, id("out"), id("a")), None);
+    }
+
+    #[test]
+    fn spanning_tree() {
+        let mut g = Graph::<(), u64>::new();
+        for (a, b, w) in [
+            ("a", "b", 4),
+            ("b", "c", 1),
+            ("a", "c", 2),
+            ("c", "d", 7),
+            ("e", "f", 3),
+        ] {
+            let (a, b) = (g.intern(a), g.intern(b));
+            g.add_undirected_edge(a, b, w);
+        }
+        let tree = minimum_spanning_tree(&g, |&w| w);
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.iter().map(|&(_, _, w)| w).sum::<u64>(), 13);
+    }
+
+    #[test]
+    fn min_cut() {
+        // two triangles joined by a single edge of weight 2
+        let mut g = Graph::<(), u64>::new();
+        for (a, b, w) in [("a", "b", 3), ("b", "c", 3), ("a", "c", 3), ("c", "d", 2)] {
+            let (a, b) = (g.intern(a), g.intern(b));
+            g.add_undirected_edge(a, b, w);
+        }
+        for (a, b) in [("d", "e"), ("e", "f"), ("d", "f")] {
+            let (a, b) = (g.intern(a), g.intern(b));
+            g.add_undirected_edge(a, b, 5);
+        }
+        let (cut, side) = minimum_cut(&g, |&w| w).unwrap();
+        assert_eq!(cut, 2);
+        let mut side = labels(&g, &side);
+        side.sort();
+        assert!(side == ["a", "b", "c"] || side == ["d", "e", "f"]);
+    }
+}
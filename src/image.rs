@@ -0,0 +1,453 @@
+/*
+** src/image.rs
+*/
+
+use crate::types::Grid;
+
+use clap::ValueEnum;
+use log::{info, warn};
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+pub type Rgb = [u8; 3];
+
+// maximum number of colours in a GIF palette and the maximum LZW code size
+const GIF_MAX_COLOURS: usize = 256;
+const GIF_MAX_CODE_SIZE: u32 = 12;
+// largest block of data which can be stored uncompressed in a deflate stream
+const DEFLATE_MAX_STORED: usize = 65535;
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xEDB88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+fn crc32(chunks: &[&[u8]]) -> u32 {
+    let mut crc = 0xFFFFFFFF;
+    for chunk in chunks {
+        for &byte in chunk.iter() {
+            crc = CRC_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+        }
+    }
+    crc ^ 0xFFFFFFFF
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[derive(Clone)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Renders a grid with each cell drawn as a square of scale x scale pixels, coloured by the
+    /// given function
+    pub fn from_grid<T, F>(grid: &Grid<T>, scale: usize, colour: F) -> Self
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let scale = scale.max(1);
+        let width = grid.width * scale;
+        let height = grid.height * scale;
+        let mut pixels = Vec::with_capacity(width * height);
+        for i in 0..grid.height {
+            let row = grid
+                .iter_row(i)
+                .flat_map(|cell| [colour(cell)].repeat(scale))
+                .collect::<Vec<_>>();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&row);
+            }
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Encodes the image as a binary (P6) PPM
+    pub fn encode_ppm(&self) -> Vec<u8> {
+        let mut output = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        output.extend(self.pixels.iter().flatten());
+        output
+    }
+
+    /// Encodes the image as an uncompressed RGB PNG
+    pub fn encode_png(&self) -> Vec<u8> {
+        fn chunk(output: &mut Vec<u8>, ty: &[u8], data: &[u8]) {
+            output.extend((data.len() as u32).to_be_bytes());
+            output.extend(ty);
+            output.extend(data);
+            output.extend(crc32(&[ty, data]).to_be_bytes());
+        }
+
+        // each scanline is prefixed by its filter type, which is always none
+        let mut raw = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.pixels.chunks(self.width.max(1)) {
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+        // zlib stream made of stored deflate blocks
+        let mut zlib = vec![0x78, 0x01];
+        let blocks = raw.chunks(DEFLATE_MAX_STORED).collect::<Vec<_>>();
+        for (i, block) in blocks.iter().enumerate() {
+            zlib.push((i == blocks.len() - 1) as u8);
+            zlib.extend((block.len() as u16).to_le_bytes());
+            zlib.extend((!(block.len() as u16)).to_le_bytes());
+            zlib.extend(block.iter());
+        }
+        zlib.extend(adler32(&raw).to_be_bytes());
+
+        let mut header = Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8-bit depth, RGB colour, default compression/filtering, no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut output = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        chunk(&mut output, b"IHDR", &header);
+        chunk(&mut output, b"IDAT", &zlib);
+        chunk(&mut output, b"IEND", &[]);
+        output
+    }
+}
+
+/// Builds the palette shared by all frames of an animation, falling back to a 6x6x6 colour cube
+/// if the frames use too many distinct colours
+fn gif_palette(frames: &[Image]) -> (Vec<Rgb>, HashMap<Rgb, u8>) {
+    let mut palette = Vec::new();
+    let mut indices = HashMap::new();
+    for &pixel in frames.iter().flat_map(|frame| frame.pixels.iter()) {
+        if let Entry::Vacant(entry) = indices.entry(pixel) {
+            entry.insert(palette.len() as u8);
+            palette.push(pixel);
+            if palette.len() > GIF_MAX_COLOURS {
+                break;
+            }
+        }
+    }
+    if palette.len() <= GIF_MAX_COLOURS {
+        return (palette, indices);
+    }
+
+    let level = |c: u8| ((c as u16 * 5 + 127) / 255) as u8;
+    let palette = (0..216)
+        .map(|n| {
+            [
+                (n / 36) as u8 * 51,
+                ((n / 6) % 6) as u8 * 51,
+                (n % 6) as u8 * 51,
+            ]
+        })
+        .collect();
+    let indices = frames
+        .iter()
+        .flat_map(|frame| frame.pixels.iter())
+        .map(|&[r, g, b]| ([r, g, b], level(r) * 36 + level(g) * 6 + level(b)))
+        .collect();
+    (palette, indices)
+}
+
+fn gif_lzw(indices: &[u8], min_code_size: u32) -> Vec<u8> {
+    struct BitWriter {
+        bytes: Vec<u8>,
+        buffer: u32,
+        bits: u32,
+    }
+
+    impl BitWriter {
+        fn write(&mut self, code: u32, size: u32) {
+            self.buffer |= code << self.bits;
+            self.bits += size;
+            while self.bits >= 8 {
+                self.bytes.push((self.buffer & 0xFF) as u8);
+                self.buffer >>= 8;
+                self.bits -= 8;
+            }
+        }
+
+        fn finish(mut self) -> Vec<u8> {
+            if self.bits > 0 {
+                self.bytes.push((self.buffer & 0xFF) as u8);
+            }
+            self.bytes
+        }
+    }
+
+    let clear = 1 << min_code_size;
+    let end = clear + 1;
+    let mut writer = BitWriter {
+        bytes: Vec::new(),
+        buffer: 0,
+        bits: 0,
+    };
+    let mut dictionary = HashMap::new();
+    let mut next_code = end + 1;
+    let mut code_size = min_code_size + 1;
+
+    writer.write(clear, code_size);
+    let Some((&first, rest)) = indices.split_first() else {
+        writer.write(end, code_size);
+        return writer.finish();
+    };
+    let mut prefix = first as u32;
+    for &index in rest {
+        if let Some(&code) = dictionary.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+        writer.write(prefix, code_size);
+        if next_code < (1 << GIF_MAX_CODE_SIZE) {
+            dictionary.insert((prefix, index), next_code);
+            next_code += 1;
+            // the decoder adds each code one step behind, so grow once it has caught up
+            if next_code > (1 << code_size) && code_size < GIF_MAX_CODE_SIZE {
+                code_size += 1;
+            }
+        } else {
+            writer.write(clear, code_size);
+            dictionary.clear();
+            next_code = end + 1;
+            code_size = min_code_size + 1;
+        }
+        prefix = index as u32;
+    }
+    writer.write(prefix, code_size);
+    // the decoder adds a code for the last one written too, and may grow to fit it
+    if next_code == (1 << code_size) && code_size < GIF_MAX_CODE_SIZE {
+        code_size += 1;
+    }
+    writer.write(end, code_size);
+    writer.finish()
+}
+
+/// Encodes a sequence of equally-sized frames as a looping animated GIF, with the given delay
+/// between frames in hundredths of a second
+pub fn encode_gif(frames: &[Image], delay: u16) -> Vec<u8> {
+    let (width, height) = frames
+        .first()
+        .map_or((0, 0), |frame| (frame.width as u16, frame.height as u16));
+    let (mut palette, indices) = gif_palette(frames);
+    // the colour table holds 2^(n+1) entries
+    let table_bits = (palette.len().max(2) as u32)
+        .next_power_of_two()
+        .trailing_zeros();
+    palette.resize(1 << table_bits, [0, 0, 0]);
+
+    let mut output = b"GIF89a".to_vec();
+    output.extend(width.to_le_bytes());
+    output.extend(height.to_le_bytes());
+    output.extend([0xF0 | (table_bits as u8 - 1), 0, 0]);
+    output.extend(palette.iter().flatten());
+    // loop forever
+    output.extend([0x21, 0xFF, 0x0B]);
+    output.extend(b"NETSCAPE2.0");
+    output.extend([0x03, 0x01, 0x00, 0x00, 0x00]);
+
+    let min_code_size = table_bits.max(2);
+    for frame in frames {
+        output.extend([0x21, 0xF9, 0x04, 0x00]);
+        output.extend(delay.to_le_bytes());
+        output.extend([0x00, 0x00]);
+
+        output.push(0x2C);
+        output.extend([0, 0, 0, 0]);
+        output.extend((frame.width as u16).to_le_bytes());
+        output.extend((frame.height as u16).to_le_bytes());
+        output.push(0x00);
+
+        let frame_indices = frame
+            .pixels
+            .iter()
+            .map(|pixel| indices[pixel])
+            .collect::<Vec<_>>();
+        output.push(min_code_size as u8);
+        for block in gif_lzw(&frame_indices, min_code_size).chunks(255) {
+            output.push(block.len() as u8);
+            output.extend(block);
+        }
+        output.push(0x00);
+    }
+    output.push(0x3B);
+    output
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ImageFormat {
+    Png,
+    Ppm,
+}
+
+struct ExportConfig {
+    directory: PathBuf,
+    format: ImageFormat,
+}
+
+static EXPORT: OnceLock<ExportConfig> = OnceLock::new();
+// frames of the animations recorded so far, written out by finish()
+static ANIMATIONS: Mutex<Vec<(String, Vec<Image>)>> = Mutex::new(Vec::new());
+// delay between animation frames, in hundredths of a second
+const ANIMATION_DELAY: u16 = 10;
+
+/// Turns on image exports for the remainder of the run
+pub fn enable_export(directory: PathBuf, format: ImageFormat) {
+    let _ = EXPORT.set(ExportConfig { directory, format });
+}
+
+fn write(name: &str, extension: &str, data: Vec<u8>) {
+    let Some(config) = EXPORT.get() else {
+        return;
+    };
+    let path = config.directory.join(format!("{}.{}", name, extension));
+    match fs::create_dir_all(&config.directory).and_then(|_| fs::write(&path, data)) {
+        Ok(_) => info!("Exported {}", path.to_string_lossy()),
+        Err(err) => warn!("failed to export {}: {}", path.to_string_lossy(), err),
+    }
+}
+
+/// Exports a single image; the image is only built if exports are enabled
+pub fn export<F>(name: &str, build: F)
+where
+    F: FnOnce() -> Image,
+{
+    if let Some(config) = EXPORT.get() {
+        let image = build();
+        match config.format {
+            ImageFormat::Png => write(name, "png", image.encode_png()),
+            ImageFormat::Ppm => write(name, "ppm", image.encode_ppm()),
+        }
+    }
+}
+
+/// Records a frame of the named animation; the frame is only built if exports are enabled
+pub fn export_frame<F>(name: &str, build: F)
+where
+    F: FnOnce() -> Image,
+{
+    if EXPORT.get().is_some() {
+        let image = build();
+        let mut animations = ANIMATIONS.lock().unwrap();
+        match animations.iter_mut().find(|(n, _)| n == name) {
+            Some((_, frames)) => frames.push(image),
+            None => animations.push((name.to_string(), vec![image])),
+        }
+    }
+}
+
+/// Writes out any recorded animations
+pub fn finish() {
+    let animations = std::mem::take(&mut *ANIMATIONS.lock().unwrap());
+    for (name, frames) in animations {
+        write(&name, "gif", encode_gif(&frames, ANIMATION_DELAY));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // decodes a GIF LZW stream, growing the code size one code after the encoder does
+    fn lzw_decode(data: &[u8], min_code_size: u32) -> Vec<u8> {
+        let clear = 1 << min_code_size;
+        let end = clear + 1;
+        let mut bits = data
+            .iter()
+            .flat_map(|&byte| (0..8).map(move |bit| ((byte >> bit) & 1) as u32));
+        let mut read = |size: u32| (0..size).map(|i| bits.next().unwrap() << i).sum::<u32>();
+
+        let mut output = Vec::new();
+        let mut dictionary = Vec::<Vec<u8>>::new();
+        let mut code_size = min_code_size + 1;
+        let mut previous: Option<Vec<u8>> = None;
+        loop {
+            let code = read(code_size);
+            if code == clear {
+                dictionary = (0..clear).map(|i| vec![i as u8]).collect();
+                dictionary.extend([Vec::new(), Vec::new()]);
+                code_size = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == end {
+                return output;
+            }
+            let entry = match dictionary.get(code as usize) {
+                Some(entry) => entry.clone(),
+                // the code the encoder has only just added, which starts and ends the same
+                None => {
+                    let mut entry = previous.clone().unwrap();
+                    entry.push(entry[0]);
+                    entry
+                }
+            };
+            if let Some(mut previous) = previous
+                && dictionary.len() < (1 << GIF_MAX_CODE_SIZE)
+            {
+                previous.push(entry[0]);
+                dictionary.push(previous);
+                if dictionary.len() == (1 << code_size) && code_size < GIF_MAX_CODE_SIZE {
+                    code_size += 1;
+                }
+            }
+            output.extend(&entry);
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(&[b"123456789"]), 0xCBF43926);
+        assert_eq!(crc32(&[b"1234", b"56789"]), 0xCBF43926);
+        assert_eq!(crc32(&[]), 0);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E60398);
+        assert_eq!(adler32(b""), 1);
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        assert_eq!(lzw_decode(&gif_lzw(&[], 2), 2), Vec::<u8>::new());
+        let repeated = [0, 1, 1, 1, 1, 2, 2, 0, 0, 0, 0, 0, 3, 3];
+        assert_eq!(lzw_decode(&gif_lzw(&repeated, 2), 2), repeated);
+
+        // enough noise to fill the dictionary, so that the encoder has to clear it
+        let mut state = 1u32;
+        let noise = (0..50_000)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as u8
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lzw_decode(&gif_lzw(&noise, 8), 8), noise);
+    }
+}
@@ -32,6 +32,24 @@ macro_rules! puzzle_modules {
                     _ => panic!("PuzzleSet::dispatch: invalid year: {}", year),
                 }
             }
+
+            pub fn reference(year: usize, day: usize) -> Option<fn(String) -> Solution> {
+                match year {
+                    $(
+                        $year => $module::PuzzleSet::reference(day),
+                    )*
+                    _ => panic!("PuzzleSet::reference: invalid year: {}", year),
+                }
+            }
+
+            pub fn generator(year: usize, day: usize) -> Option<Generator> {
+                match year {
+                    $(
+                        $year => $module::PuzzleSet::generator(day),
+                    )*
+                    _ => panic!("PuzzleSet::generator: invalid year: {}", year),
+                }
+            }
         }
     };
 }
@@ -39,8 +57,12 @@ macro_rules! puzzle_modules {
 #[macro_export]
 #[allow(clippy::crate_in_macro_def)]
 macro_rules! puzzle_set {
-    ($($module:ident),+) => {
-        use crate::puzzles::Solution;
+    (
+        $($module:ident),+
+        $(; reference: $($reference:ident),+)?
+        $(; generator: $($generator:ident),+)?
+    ) => {
+        use crate::puzzles::{Generator, Solution};
 
         $(mod $module;)*
 
@@ -67,6 +89,28 @@ macro_rules! puzzle_set {
                     panic!("PuzzleSet::dispatch: invalid day: {}", day)
                 }
             }
+
+            #[allow(unused_variables)]
+            pub fn reference(day: usize) -> Option<fn(String) -> Solution> {
+                let module = format!("day{}", day + 1);
+                $($(
+                    if module == stringify!($reference) {
+                        return Some($reference::solve_reference);
+                    }
+                )+)?
+                None
+            }
+
+            #[allow(unused_variables)]
+            pub fn generator(day: usize) -> Option<Generator> {
+                let module = format!("day{}", day + 1);
+                $($(
+                    if module == stringify!($generator) {
+                        return Some($generator::generate);
+                    }
+                )+)?
+                None
+            }
         }
     };
 }
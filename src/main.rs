@@ -2,18 +2,33 @@
 ** src/main.rs
 */
 
+mod automaton;
+mod cycle;
 mod driver;
+mod geometry;
+mod graph;
+mod image;
 mod itertools;
 mod macros;
 mod puzzles;
+mod random;
+mod search;
+mod server;
 mod types;
 mod utils;
+mod visualize;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use simplelog::{LevelFilter, TermLogger};
 
+use std::path::PathBuf;
+use std::time::Duration;
+
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
     /// Year, runs all if not provided
     year: Option<usize>,
     /// Day, runs all if not provided
@@ -24,14 +39,61 @@ struct Args {
     /// Benchmark puzzle solutions and produce a report
     #[arg(short, long)]
     benchmark: bool,
+    /// Compare solutions against their reference implementations
+    #[arg(long)]
+    cross_check: bool,
     /// Number of iterations to use when benchmarking
     #[arg(long, id = "N", default_value_t = 10)]
     benchmark_iterations: usize,
+    /// Benchmark solutions against generated inputs of increasing size
+    #[arg(long, requires = "benchmark")]
+    scale: bool,
+    /// Input sizes to use when benchmarking with generated inputs
+    #[arg(long, value_delimiter = ',', default_value = "16,32,64,128,256,512")]
+    scale_sizes: Vec<usize>,
+    /// Render simulations in the terminal as they run
+    #[arg(long)]
+    visualize: bool,
+    /// Frames per second when rendering simulations
+    #[arg(long, default_value_t = 30, requires = "visualize")]
+    fps: u32,
+    /// Export images and animations of puzzle state to the given directory
+    #[arg(long, id = "DIR")]
+    export: Option<PathBuf>,
+    /// Image format for exported single frames
+    #[arg(long, value_enum, default_value_t = image::ImageFormat::Png, requires = "DIR")]
+    export_format: image::ImageFormat,
+    /// Seed for generated inputs
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
     /// Enable debug output
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     debug: bool,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Run a local HTTP server which solves posted puzzle inputs
+    Serve {
+        /// Port to listen on
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+        /// Maximum size of a request body, in bytes
+        #[arg(long, default_value_t = 1 << 20)]
+        max_body_size: usize,
+        /// Time allowed for each puzzle solution, in milliseconds
+        #[arg(long, default_value_t = 10_000)]
+        timeout: u64,
+        /// Maximum number of solvers running at once, including any which have timed out
+        #[arg(long, default_value_t = 4)]
+        max_solvers: usize,
+        /// Number of connections handled at once, with as many more waiting before new ones are
+        /// turned away
+        #[arg(long, default_value_t = 16)]
+        max_connections: usize,
+    },
+}
+
 fn initialize_logger(debug: bool) {
     let log_level = if debug {
         LevelFilter::Debug
@@ -50,8 +112,45 @@ fn initialize_logger(debug: bool) {
 fn main() {
     let args = Args::parse();
     initialize_logger(args.debug);
+    if args.visualize {
+        visualize::enable(args.fps);
+    }
+    if let Some(directory) = args.export {
+        image::enable_export(directory, args.export_format);
+    }
 
-    if args.benchmark {
+    if let Some(Command::Serve {
+        port,
+        max_body_size,
+        timeout,
+        max_solvers,
+        max_connections,
+    }) = args.command
+    {
+        // Serve puzzle solutions over HTTP
+        let config = server::ServerConfig {
+            port,
+            max_body_size,
+            timeout: Duration::from_millis(timeout),
+            max_solvers,
+            max_connections,
+        };
+        if let Err(err) = server::serve(config) {
+            log::error!("Server error: {}", err);
+        }
+    } else if args.cross_check {
+        // Compare optimized solutions against their reference implementations
+        driver::run_cross_check(args.year, args.day, args.seed);
+    } else if args.scale {
+        // Benchmark solutions against generated inputs of increasing size
+        driver::run_scale_benchmark(
+            args.year,
+            args.day,
+            &args.scale_sizes,
+            args.benchmark_iterations,
+            args.seed,
+        );
+    } else if args.benchmark {
         // Run benchmarks and produce a report
         driver::run_benchmark(args.benchmark_iterations);
     } else {
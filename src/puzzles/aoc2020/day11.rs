@@ -3,9 +3,12 @@
 */
 
 use super::Solution;
-use crate::types::{Direction, Grid};
+use crate::automaton::{GridAutomaton, Neighborhood};
+use crate::image::{self, Image};
+use crate::types::Grid;
+use crate::visualize;
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, PartialEq)]
 enum State {
     #[default]
     Floor,
@@ -14,6 +17,10 @@ enum State {
 }
 
 impl State {
+    fn is_floor(&self) -> bool {
+        matches!(self, Self::Floor)
+    }
+
     fn is_empty(&self) -> bool {
         matches!(self, Self::Empty)
     }
@@ -22,10 +29,19 @@ impl State {
         matches!(self, Self::Occupied)
     }
 
-    fn occupied(&self) -> usize {
+    fn to_rgb(self) -> image::Rgb {
+        match self {
+            Self::Floor => [40, 40, 40],
+            Self::Empty => [90, 160, 230],
+            Self::Occupied => [230, 90, 60],
+        }
+    }
+
+    fn to_char(self) -> char {
         match self {
-            Self::Occupied => 1,
-            _ => 0,
+            Self::Floor => '.',
+            Self::Empty => 'L',
+            Self::Occupied => '#',
         }
     }
 }
@@ -41,121 +57,40 @@ impl From<char> for State {
     }
 }
 
-enum Visibility {
-    Adjacent,
-    LineOfSight,
-    NotSet,
-}
-
 // the ferry seating is a cellular automaton
 //
 // the rule is:
 // if a seat is empty and there are no occupied seats in the neighborhood, the
-// seat becomes occupied; if a seat is occupied and 4 or more seats in the
-// neighborhood are also occupied, the seat becomes empty; otherwise, no change
-struct FerryAutomaton {
-    grid: Grid<State>,
-    // rule configuration
-    visibility: Visibility,
+// seat becomes occupied; if a seat is occupied and occupied_threshold or more
+// seats in the neighborhood are also occupied, the seat becomes empty;
+// otherwise, no change
+fn occupied_at_equilibrium(
+    seats: &Grid<State>,
+    neighborhood: Neighborhood<State>,
     occupied_threshold: usize,
-}
-
-impl FerryAutomaton {
-    fn with(mut self, visibility: Visibility, occupied_threshold: usize) -> Self {
-        self.visibility = visibility;
-        self.occupied_threshold = occupied_threshold;
-        self
-    }
-
-    fn occupied_adjacent(&self, i: usize, j: usize) -> usize {
-        self.grid
-            .neighbors_with_diagonal(i, j)
-            .into_iter()
-            .map(|(i, j)| self.grid.get(i, j).occupied())
-            .sum()
-    }
-
-    fn check_line_of_sight(&self, mut i: usize, mut j: usize, direction: Direction) -> bool {
-        while let Some((ii, jj)) = self.grid.neighbor(i, j, direction) {
-            match self.grid.get(ii, jj) {
-                State::Occupied => return true,
-                State::Empty => return false,
-                _ => {
-                    i = ii;
-                    j = jj;
-                }
-            }
+    name: &str,
+) -> usize {
+    let mut automaton = GridAutomaton::new(seats.clone(), neighborhood, |state, neighbors| {
+        let occupied = neighbors.iter().filter(|state| state.is_occupied()).count();
+        if state.is_empty() && occupied == 0 {
+            State::Occupied
+        } else if state.is_occupied() && occupied >= occupied_threshold {
+            State::Empty
+        } else {
+            *state
         }
-        false
-    }
-
-    fn occupied_line_of_sight(&self, i: usize, j: usize) -> usize {
-        Direction::all_cases()
-            .map(|direction| self.check_line_of_sight(i, j, direction))
-            .filter(|&found| found)
-            .count()
-    }
-
-    fn visible_occupied(&self, row: usize, col: usize) -> usize {
-        match self.visibility {
-            Visibility::Adjacent => self.occupied_adjacent(row, col),
-            Visibility::LineOfSight => self.occupied_line_of_sight(row, col),
-            _ => unreachable!(),
-        }
-    }
-
-    // creates the next generation of the automaton by applying the rule to the
-    // current generation; returns the number of cells that changed state
-    fn run(&mut self) -> usize {
-        let mut next = self.grid.clone();
-        let mut changed = 0;
-        // iterate thru the grid, accounting for the padding along the borders
-        for (i, j, state) in self.grid.iter_grid() {
-            // note: save a bit by not checking adjacencies for the floor
-            let next_state = if state.is_empty() && self.visible_occupied(i, j) == 0 {
-                changed += 1;
-                State::Occupied
-            } else if state.is_occupied() && self.visible_occupied(i, j) >= self.occupied_threshold
-            {
-                changed += 1;
-                State::Empty
-            } else {
-                *state
-            };
-            next.set(i, j, next_state);
-        }
-        self.grid = next;
-        changed
-    }
-
-    fn run_to_completion(&mut self) {
-        let mut changed = usize::MAX;
-        while changed > 0 {
-            changed = self.run();
-        }
-    }
-
-    fn occupied_seats(&self) -> u64 {
-        self.grid
-            .iter_grid()
-            .filter(|(_, _, cell)| cell.is_occupied())
-            .count() as u64
-    }
-}
-
-impl From<&str> for FerryAutomaton {
-    fn from(value: &str) -> Self {
-        let grid = value
-            .split('\n')
-            .map(|line| line.chars().map(State::from))
-            .collect::<Grid<_>>();
-        Self {
-            grid,
-            // default options, call Self::with() afterwards
-            visibility: Visibility::NotSet,
-            occupied_threshold: 0,
+    });
+    loop {
+        let changed = automaton.step();
+        visualize::frame(|| automaton.grid.map(|state| state.to_char()));
+        image::export_frame(name, || {
+            Image::from_grid(&automaton.grid, 4, |state| state.to_rgb())
+        });
+        if changed == 0 {
+            break;
         }
     }
+    automaton.count(State::is_occupied)
 }
 
 pub fn solve(input: String) -> Solution {
@@ -163,15 +98,24 @@ pub fn solve(input: String) -> Solution {
 
     // Part A: Simulate your seating area by applying the seating rules repeatedly until no seats
     // change state. How many seats end up occupied?
-    let mut automaton = FerryAutomaton::from(input.as_str()).with(Visibility::Adjacent, 4);
-    automaton.run_to_completion();
-    solution.set_part_a(automaton.occupied_seats());
+    let seats = input
+        .split('\n')
+        .map(|line| line.chars().map(State::from))
+        .collect::<Grid<_>>();
+    let occupied =
+        occupied_at_equilibrium(&seats, Neighborhood::Moore, 4, "aoc2020_day11_adjacent");
+    solution.set_part_a(occupied);
 
     // Part B: Given the new visibility method and the rule change for occupied seats becoming
     // empty, once equilibrium is reached, how many seats end up occupied?
-    let mut automaton = FerryAutomaton::from(input.as_str()).with(Visibility::LineOfSight, 5);
-    automaton.run_to_completion();
-    solution.set_part_b(automaton.occupied_seats());
+    // people look past the floor to the first seat in each direction
+    let occupied = occupied_at_equilibrium(
+        &seats,
+        Neighborhood::LineOfSight(State::is_floor),
+        5,
+        "aoc2020_day11_line_of_sight",
+    );
+    solution.set_part_b(occupied);
 
     solution
 }
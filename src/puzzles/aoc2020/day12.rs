@@ -3,195 +3,77 @@
 */
 
 use super::Solution;
+use crate::types::{Cardinal, Point};
 
 #[derive(Clone, Copy)]
-enum NavigationDirection {
-    North,
-    South,
-    East,
-    West,
-    Left,
-    Right,
+enum Action {
+    Move(Cardinal),
+    // degrees to turn clockwise
+    Turn(i64),
     Forward,
 }
 
-impl NavigationDirection {
-    fn is_cardinal(&self) -> bool {
-        matches!(
-            self,
-            NavigationDirection::North
-                | NavigationDirection::South
-                | NavigationDirection::East
-                | NavigationDirection::West
-        )
-    }
-
-    fn is_rotational(&self) -> bool {
-        matches!(self, NavigationDirection::Left | NavigationDirection::Right)
-    }
-
-    fn rotate_left(&mut self) {
-        match self {
-            NavigationDirection::North => *self = NavigationDirection::West,
-            NavigationDirection::South => *self = NavigationDirection::East,
-            NavigationDirection::East => *self = NavigationDirection::North,
-            NavigationDirection::West => *self = NavigationDirection::South,
-            _ => unreachable!(),
-        }
-    }
-
-    fn rotate_right(&mut self) {
-        match self {
-            NavigationDirection::North => *self = NavigationDirection::East,
-            NavigationDirection::South => *self = NavigationDirection::West,
-            NavigationDirection::East => *self = NavigationDirection::South,
-            NavigationDirection::West => *self = NavigationDirection::North,
-            _ => unreachable!(),
-        }
-    }
-}
-
-impl From<char> for NavigationDirection {
-    fn from(c: char) -> Self {
-        match c {
-            'N' => NavigationDirection::North,
-            'S' => NavigationDirection::South,
-            'E' => NavigationDirection::East,
-            'W' => NavigationDirection::West,
-            'L' => NavigationDirection::Left,
-            'R' => NavigationDirection::Right,
-            'F' => NavigationDirection::Forward,
-            _ => unreachable!(),
-        }
-    }
-}
-
 struct NavigationInstruction {
-    direction: NavigationDirection,
-    distance: i32,
+    action: Action,
+    distance: i64,
 }
 
 impl From<&str> for NavigationInstruction {
     fn from(s: &str) -> Self {
-        let direction = NavigationDirection::from(s.chars().next().unwrap());
-        let distance = s[1..s.len()].parse().unwrap();
+        let distance = s[1..s.len()].parse::<i64>().unwrap();
+        let action = match s.chars().next().unwrap() {
+            'L' => Action::Turn(-distance),
+            'R' => Action::Turn(distance),
+            'F' => Action::Forward,
+            c => Action::Move(Cardinal::try_from(c).unwrap()),
+        };
 
-        Self {
-            direction,
-            distance,
-        }
+        Self { action, distance }
     }
 }
 
 struct Navigator<I> {
-    x: i32,
-    y: i32,
-    direction: NavigationDirection,
+    // positions have y increasing to the south, as with grids
+    ship: Point,
+    heading: Cardinal,
     instructions: I,
-    waypoint: Option<(i32, i32)>,
+    waypoint: Option<Point>,
 }
 
 impl<I> Navigator<I> {
-    fn with_waypoint(mut self, x: i32, y: i32) -> Self {
-        self.waypoint = Some((x, y));
+    fn with_waypoint(mut self, waypoint: Point) -> Self {
+        self.waypoint = Some(waypoint);
         self
     }
 
-    fn direction_to_dx_dy(direction: NavigationDirection, distance: i32) -> (i32, i32) {
-        match direction {
-            NavigationDirection::North => (0, distance),
-            NavigationDirection::South => (0, -distance),
-            NavigationDirection::East => (distance, 0),
-            NavigationDirection::West => (-distance, 0),
-            _ => unreachable!(),
-        }
-    }
-
-    fn move_ship(&mut self, dx: i32, dy: i32) {
-        self.x += dx;
-        self.y += dy;
+    fn move_forward(&mut self, distance: i64) {
+        let step = self.waypoint.unwrap_or(self.heading.point_delta());
+        self.ship = self.ship + Point::new(step.x * distance, step.y * distance);
     }
 
-    fn move_waypoint(&mut self, dx: i32, dy: i32) {
-        if let Some((x, y)) = self.waypoint {
-            self.waypoint = Some((x + dx, y + dy));
-        } else {
-            unreachable!()
-        }
-    }
-
-    fn move_forward(&mut self, distance: i32) {
-        if let Some((wx, wy)) = self.waypoint {
-            for _ in 0..distance {
-                self.move_ship(wx, wy);
-            }
-        } else {
-            let (dx, dy) = Self::direction_to_dx_dy(self.direction, distance);
-            self.move_ship(dx, dy);
-        }
-    }
-
-    fn moves(&mut self, direction: NavigationDirection, distance: i32) {
-        assert!(direction.is_cardinal());
-
-        let (dx, dy) = Self::direction_to_dx_dy(direction, distance);
+    fn moves(&mut self, direction: Cardinal, distance: i64) {
+        let delta = direction.point_delta();
+        let delta = Point::new(delta.x * distance, delta.y * distance);
 
         // move the waypoint, if it is set
         // otherwise move the ship
-        if self.waypoint.is_some() {
-            self.move_waypoint(dx, dy);
+        if let Some(waypoint) = self.waypoint {
+            self.waypoint = Some(waypoint + delta);
         } else {
-            self.move_ship(dx, dy);
-        }
-    }
-
-    fn rotate_ship(&mut self, direction: NavigationDirection, degrees: i32) {
-        let rotator = match direction {
-            NavigationDirection::Left => NavigationDirection::rotate_left,
-            NavigationDirection::Right => NavigationDirection::rotate_right,
-            _ => unreachable!(),
-        };
-
-        for _ in 0..(degrees / 90) {
-            rotator(&mut self.direction);
+            self.ship = self.ship + delta;
         }
     }
 
-    fn rotate_waypoint(&mut self, direction: NavigationDirection, degrees: i32) {
-        if let Some((mut x, mut y)) = self.waypoint {
-            match direction {
-                NavigationDirection::Left => {
-                    for _ in 0..(degrees / 90) {
-                        let t = x;
-                        x = -y;
-                        y = t;
-                    }
-                }
-                NavigationDirection::Right => {
-                    for _ in 0..(degrees / 90) {
-                        let t = y;
-                        y = -x;
-                        x = t;
-                    }
-                }
-                _ => unreachable!(),
-            };
-            self.waypoint = Some((x, y));
-        } else {
-            unreachable!()
-        }
-    }
-
-    fn rotates(&mut self, direction: NavigationDirection, degrees: i32) {
-        assert!(direction.is_rotational());
-        assert!(degrees % 90 == 0);
-
-        // rotate the waypoint, if it is set
+    fn rotates(&mut self, degrees: i64) {
+        // rotate the waypoint around the ship, if it is set
         // otherwise rotate the ship
-        if self.waypoint.is_some() {
-            self.rotate_waypoint(direction, degrees);
+        if let Some(mut waypoint) = self.waypoint {
+            for _ in 0..(degrees.rem_euclid(360) / 90) {
+                waypoint = Point::new(-waypoint.y, waypoint.x);
+            }
+            self.waypoint = Some(waypoint);
         } else {
-            self.rotate_ship(direction, degrees);
+            self.heading = self.heading.rotate(degrees);
         }
     }
 }
@@ -202,10 +84,9 @@ where
 {
     fn from(instructions: I) -> Self {
         Self {
-            x: 0,
-            y: 0,
+            ship: Point::origin(),
             // ship starts facing East
-            direction: NavigationDirection::East,
+            heading: Cardinal::East,
             instructions,
             waypoint: None,
         }
@@ -217,28 +98,20 @@ where
     I: Iterator<Item = &'a NavigationInstruction>,
 {
     // each iteration returns the new position
-    type Item = (i32, i32);
+    type Item = Point;
 
     fn next(&mut self) -> Option<Self::Item> {
         // consume instructions until they have been exhausted
-        if let Some(instr) = self.instructions.next() {
-            match instr.direction {
-                dir if dir.is_cardinal() => self.moves(dir, instr.distance),
-                dir if dir.is_rotational() => self.rotates(dir, instr.distance),
-                NavigationDirection::Forward => self.move_forward(instr.distance),
-                _ => unreachable!(),
-            };
-            Some((self.x, self.y))
-        } else {
-            None
-        }
+        let instr = self.instructions.next()?;
+        match instr.action {
+            Action::Move(direction) => self.moves(direction, instr.distance),
+            Action::Turn(degrees) => self.rotates(degrees),
+            Action::Forward => self.move_forward(instr.distance),
+        };
+        Some(self.ship)
     }
 }
 
-fn distance(x: i32, y: i32) -> i32 {
-    x.abs() + y.abs()
-}
-
 pub fn solve(input: String) -> Solution {
     let mut solution = Solution::new();
     let instructions = input
@@ -248,16 +121,17 @@ pub fn solve(input: String) -> Solution {
 
     // Part A: Figure out where the navigation instructions lead. What is the Manhattan distance
     // between that location and the ship's starting position?
-    let (x, y) = Navigator::from(instructions.iter()).last().unwrap();
-    solution.set_part_a(distance(x, y));
+    let ship = Navigator::from(instructions.iter()).last().unwrap();
+    solution.set_part_a(Point::manhattan_distance(ship, Point::origin()));
 
     // Part B: Figure out where the navigation instructions actually lead (using the waypoint).
     // What is the Manhattan distance between that location and the ship's starting position?
-    let (x, y) = Navigator::from(instructions.iter())
-        .with_waypoint(10, 1)
+    // the waypoint starts 10 units east and 1 unit north of the ship
+    let ship = Navigator::from(instructions.iter())
+        .with_waypoint(Point::new(10, -1))
         .last()
         .unwrap();
-    solution.set_part_b(distance(x, y));
+    solution.set_part_b(Point::manhattan_distance(ship, Point::origin()));
 
     solution
 }
@@ -3,6 +3,7 @@
 */
 
 use super::Solution;
+use crate::random::{self, Rng};
 use crate::types::Maze;
 
 fn traverse(map: &Maze, dx: usize, dy: usize) -> usize {
@@ -39,3 +40,11 @@ pub fn solve(input: String) -> Solution {
 
     solution
 }
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // the map is a fixed width and repeats to the right; size is the number of rows
+    let map = random::grid(rng, 31, size.max(1), |rng| {
+        if rng.chance(0.2) { '#' } else { '.' }
+    });
+    map.to_string().trim_end().to_string()
+}
@@ -27,7 +27,7 @@ fn questions_all_yes(groups: &[&str]) -> usize {
     // the number of questions to which everyone answered yes is each entry where the count is
     // the number of respondents
     for group in groups {
-        let mut counter = Counter::new();
+        let mut counter = Counter::<char>::new();
         let lines = group.split('\n').collect::<Vec<_>>();
         for response in lines.iter() {
             counter.extend(response.chars());
@@ -4,6 +4,7 @@
 
 crate::puzzle_set!(
     day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13, day14, day15,
-    day16, day17, day18
+    day16, day17, day18;
+    generator: day3
 );
 
@@ -3,6 +3,7 @@
 */
 
 use super::Solution;
+use crate::cycle::Cycle;
 use crate::types::Grid;
 
 fn run_step(cells: &mut Grid<u32>) -> u64 {
@@ -36,10 +37,7 @@ fn run_steps(mut cells: Grid<u32>, n: usize) -> u64 {
 
 pub fn solve(input: String) -> Solution {
     let mut solution = Solution::new();
-    let energy_cells = input
-        .split('\n')
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()))
-        .collect::<Grid<_>>();
+    let energy_cells = Grid::parse_with(&input, |c| c.to_digit(10)).unwrap();
 
     // Part A: Given the starting energy levels of the dumbo octopuses in your cavern, simulate 100
     // steps. How many total flashes are there after 100 steps?
@@ -47,16 +45,19 @@ pub fn solve(input: String) -> Solution {
     solution.set_part_a(flashes);
 
     // Part B: What is the first step during which all octopuses flash?
-    let mut step = None;
-    let mut cells = energy_cells.clone();
-    let all_flash = (energy_cells.width * energy_cells.height) as u64;
-    for i in 0..usize::MAX {
-        let n = run_step(&mut cells);
-        if n == all_flash {
-            step = Some(i + 1);
-            break;
-        }
-    }
+    // the energy levels eventually repeat, so if the octopuses ever all flash together, it is
+    // within the first cycle, which ends a few steps after they do since a grid of zeros cycles
+    // back after ten steps
+    let cycle = Cycle::find(energy_cells, |cells| {
+        let mut cells = cells.clone();
+        run_step(&mut cells);
+        cells
+    });
+    let step = cycle
+        .states()
+        .skip(1)
+        .find(|(_, cells)| cells.iter_grid().all(|(_, _, &cell)| cell == 0))
+        .map(|(step, _)| step);
     solution.maybe_set_part_b(step);
 
     solution
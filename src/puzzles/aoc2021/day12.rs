@@ -3,11 +3,11 @@
 */
 
 use super::Solution;
-use crate::utils;
+use crate::types::Graph;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
-type CaveMap<'a> = HashMap<&'a str, Vec<&'a str>>;
+type CaveMap = Graph<(), ()>;
 
 fn is_start(cave: &str) -> bool {
     cave == "start"
@@ -21,32 +21,29 @@ fn is_small_cave(cave: &str) -> bool {
     cave.chars().all(char::is_lowercase)
 }
 
-fn find_paths_small_caves_once_rec<'a>(
-    cave_map: &CaveMap<'a>,
-    from: &'a str,
-    mut visited: HashSet<&'a str>,
-) -> Vec<Vec<&'a str>> {
+fn find_paths_small_caves_once_rec(
+    cave_map: &CaveMap,
+    from: usize,
+    mut visited: HashSet<usize>,
+) -> Vec<Vec<usize>> {
     let mut paths = vec![];
     // add the current cave to the visited caves if it is a small cave
-    if is_small_cave(from) {
+    if is_small_cave(cave_map.label(from)) {
         visited.insert(from);
     }
 
     // recurse on un-visited caves
-    if let Some(connected_caves) = cave_map.get(from) {
-        for cave in connected_caves.iter() {
-            if !visited.contains(cave) {
-                // base case: end
-                if is_end(cave) {
-                    paths.push(vec![*cave, from]);
-                } else {
-                    let paths_rec =
-                        find_paths_small_caves_once_rec(cave_map, cave, visited.clone());
-                    // add the current cave to the paths and continue
-                    for mut path in paths_rec.into_iter() {
-                        path.push(from);
-                        paths.push(path);
-                    }
+    for cave in cave_map.neighbors(from) {
+        if !visited.contains(&cave) {
+            // base case: end
+            if is_end(cave_map.label(cave)) {
+                paths.push(vec![cave, from]);
+            } else {
+                let paths_rec = find_paths_small_caves_once_rec(cave_map, cave, visited.clone());
+                // add the current cave to the paths and continue
+                for mut path in paths_rec.into_iter() {
+                    path.push(from);
+                    paths.push(path);
                 }
             }
         }
@@ -55,63 +52,58 @@ fn find_paths_small_caves_once_rec<'a>(
     paths
 }
 
-fn find_paths_small_caves_once<'a>(cave_map: &CaveMap<'a>) -> Vec<Vec<&'a str>> {
+fn find_paths_small_caves_once(cave_map: &CaveMap) -> Vec<Vec<usize>> {
     let visited = HashSet::new();
-    find_paths_small_caves_once_rec(cave_map, "start", visited)
+    let start = cave_map.id("start").unwrap();
+    find_paths_small_caves_once_rec(cave_map, start, visited)
 }
 
-fn find_paths_small_caves_once_or_twice_rec<'a>(
-    cave_map: &CaveMap<'a>,
-    from: &'a str,
-    mut visited: HashSet<&'a str>,
+fn find_paths_small_caves_once_or_twice_rec(
+    cave_map: &CaveMap,
+    from: usize,
+    mut visited: HashSet<usize>,
     twice_visited: bool,
-) -> Vec<Vec<&'a str>> {
+) -> Vec<Vec<usize>> {
     let mut paths = vec![];
     // add the current cave to the visited caves if it is a small cave
-    if is_small_cave(from) {
+    if is_small_cave(cave_map.label(from)) {
         visited.insert(from);
     }
 
     // recurse on un-visited caves
-    if let Some(connected_caves) = cave_map.get(from) {
-        for cave in connected_caves.iter() {
-            // the small cave revisit adds the option for a second branching point
-            // if we have already visited a small cave but have not visited any small cave
-            // twice, we can (a) skip the cave or (b) continuing on with the cave
-            // note: not true for the start cave
-            if visited.contains(cave) && !twice_visited && !is_start(cave) {
-                // base case: end
-                if is_end(cave) {
-                    paths.push(vec![*cave, from]);
-                } else {
-                    let paths_rec = find_paths_small_caves_once_or_twice_rec(
-                        cave_map,
-                        cave,
-                        visited.clone(),
-                        true,
-                    );
-                    // add the current cave to the paths and continue
-                    for mut path in paths_rec.into_iter() {
-                        path.push(from);
-                        paths.push(path);
-                    }
+    for cave in cave_map.neighbors(from) {
+        // the small cave revisit adds the option for a second branching point
+        // if we have already visited a small cave but have not visited any small cave
+        // twice, we can (a) skip the cave or (b) continuing on with the cave
+        // note: not true for the start cave
+        if visited.contains(&cave) && !twice_visited && !is_start(cave_map.label(cave)) {
+            // base case: end
+            if is_end(cave_map.label(cave)) {
+                paths.push(vec![cave, from]);
+            } else {
+                let paths_rec =
+                    find_paths_small_caves_once_or_twice_rec(cave_map, cave, visited.clone(), true);
+                // add the current cave to the paths and continue
+                for mut path in paths_rec.into_iter() {
+                    path.push(from);
+                    paths.push(path);
                 }
-            } else if !visited.contains(cave) {
-                // base case: end
-                if is_end(cave) {
-                    paths.push(vec![*cave, from]);
-                } else {
-                    let paths_rec = find_paths_small_caves_once_or_twice_rec(
-                        cave_map,
-                        cave,
-                        visited.clone(),
-                        twice_visited,
-                    );
-                    // add the current cave to the paths and continue
-                    for mut path in paths_rec.into_iter() {
-                        path.push(from);
-                        paths.push(path);
-                    }
+            }
+        } else if !visited.contains(&cave) {
+            // base case: end
+            if is_end(cave_map.label(cave)) {
+                paths.push(vec![cave, from]);
+            } else {
+                let paths_rec = find_paths_small_caves_once_or_twice_rec(
+                    cave_map,
+                    cave,
+                    visited.clone(),
+                    twice_visited,
+                );
+                // add the current cave to the paths and continue
+                for mut path in paths_rec.into_iter() {
+                    path.push(from);
+                    paths.push(path);
                 }
             }
         }
@@ -119,26 +111,15 @@ fn find_paths_small_caves_once_or_twice_rec<'a>(
     paths
 }
 
-fn find_paths_small_caves_once_or_twice<'a>(cave_map: &'a CaveMap) -> Vec<Vec<&'a str>> {
+fn find_paths_small_caves_once_or_twice(cave_map: &CaveMap) -> Vec<Vec<usize>> {
     let visited = HashSet::new();
-    find_paths_small_caves_once_or_twice_rec(cave_map, "start", visited, false)
-}
-
-fn parse_caves(input: &str) -> CaveMap<'_> {
-    let mut caves = HashMap::new();
-    for line in input.split('\n') {
-        let (from, to) = utils::split(line, "-").unwrap();
-        let entry_from = caves.entry(from).or_insert_with(Vec::new);
-        entry_from.push(to);
-        let entry_to = caves.entry(to).or_insert_with(Vec::new);
-        entry_to.push(from);
-    }
-    caves
+    let start = cave_map.id("start").unwrap();
+    find_paths_small_caves_once_or_twice_rec(cave_map, start, visited, false)
 }
 
 pub fn solve(input: String) -> Solution {
     let mut solution = Solution::new();
-    let cave_map = parse_caves(&input);
+    let cave_map = CaveMap::parse_edges(&input, "-").unwrap();
 
     // Part A: How many paths through the cave system are there that visit small caves at most once?
     let caves = find_paths_small_caves_once(&cave_map);
@@ -3,7 +3,7 @@
 */
 
 use super::Solution;
-use crate::types::Point;
+use crate::types::{Point, SparseGrid};
 use crate::utils;
 
 use std::collections::HashSet;
@@ -55,22 +55,11 @@ fn perform_fold(points: HashSet<Point>, fold: &Fold) -> HashSet<Point> {
 }
 
 fn print_grid(points: HashSet<Point>) -> String {
-    let mut grid = vec![String::new()];
-    let x_max = points.iter().map(|p| p.x).max().unwrap();
-    let y_max = points.iter().map(|p| p.y).max().unwrap();
-    for y in 0..=y_max {
-        let mut s = String::with_capacity(x_max as usize);
-        let px = points
-            .iter()
-            .filter(|p| p.y == y)
-            .map(|p| p.x)
-            .collect::<HashSet<_>>();
-        for x in 0..=x_max {
-            s += if px.contains(&x) { "#" } else { " " };
-        }
-        grid.push(s);
+    let mut grid = SparseGrid::new(' ');
+    for point in points {
+        grid.set(point, '#');
     }
-    grid.join("\n")
+    format!("\n{}", grid.render(|c| *c))
 }
 
 fn parse_input(input: String) -> (HashSet<Point>, Vec<Fold>) {
@@ -8,7 +8,7 @@ use crate::utils;
 
 use std::collections::HashMap;
 
-type PairCounter = Counter<Pair>;
+type PairCounter = Counter<Pair, u64>;
 
 #[derive(Clone, Eq, Hash, PartialEq)]
 struct Pair(char, char);
@@ -48,7 +48,7 @@ fn apply_pair_insertion(rules: &HashMap<Pair, char>, pairs: PairCounter) -> Pair
     output
 }
 
-fn pair_counter_to_char_counter(pair_counts: PairCounter) -> Counter<char> {
+fn pair_counter_to_char_counter(pair_counts: PairCounter) -> Counter<char, u64> {
     let mut char_counts = Counter::new();
     for (pair, &count) in pair_counts.iter() {
         char_counts.add_many(pair.0, count);
@@ -3,9 +3,19 @@
 */
 
 use super::Solution;
-use crate::types::Grid;
+use crate::image::{self, Image};
+use crate::types::{Connectivity, Grid, Region, Regions};
 
-use std::collections::{HashSet, VecDeque};
+// colours cycled through when exporting the basins; neighboring basins are separated by walls of
+// height 9, so a short palette is enough to tell them apart
+const BASIN_COLOURS: [image::Rgb; 6] = [
+    [66, 135, 245],
+    [245, 166, 35],
+    [80, 200, 120],
+    [220, 80, 80],
+    [170, 110, 230],
+    [60, 200, 210],
+];
 
 fn is_lowpoint(heightmap: &Grid<u32>, i: usize, j: usize) -> bool {
     let here = heightmap.get(i, j);
@@ -15,39 +25,28 @@ fn is_lowpoint(heightmap: &Grid<u32>, i: usize, j: usize) -> bool {
         .all(|(ii, jj)| heightmap.get(ii, jj) > here)
 }
 
-fn basin_size(heightmap: &Grid<u32>, i: usize, j: usize) -> usize {
-    // points to be explored
-    let mut frontier = VecDeque::new();
-    // points already explored
-    let mut explored = HashSet::new();
+fn basins(heightmap: &Grid<u32>) -> Regions {
+    // basins are separated by points at the maximum height (9), which are not part of any basin
+    heightmap.label_regions(Connectivity::Four, |&a, &b| (a < 9) == (b < 9))
+}
 
-    // start with the given point
-    frontier.push_back((i, j));
+fn is_basin(heightmap: &Grid<u32>, region: &Region) -> bool {
+    heightmap[region.start] < 9
+}
 
-    while !frontier.is_empty() {
-        // pop from the front of the frontier
-        let (ii, jj) = frontier.pop_front().unwrap();
-        // add unexplored neighbors to the frontier
-        // note: exclude neighbors at the maximum height (9)
-        for (iii, jjj) in heightmap.neighbors(ii, jj) {
-            let v = *heightmap.get(iii, jjj);
-            if !explored.contains(&(iii, jjj)) && v < 9 {
-                frontier.push_back((iii, jjj));
-            }
+fn basins_image(heightmap: &Grid<u32>, basins: &Regions) -> Image {
+    Image::from_grid(&basins.labels, 4, |&label| {
+        if is_basin(heightmap, &basins.regions[label]) {
+            BASIN_COLOURS[label % BASIN_COLOURS.len()]
+        } else {
+            [30, 30, 30]
         }
-        // add the current point to the explored set
-        explored.insert((ii, jj));
-    }
-
-    explored.len()
+    })
 }
 
 pub fn solve(input: String) -> Solution {
     let mut solution = Solution::new();
-    let heightmap = input
-        .split('\n')
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()))
-        .collect::<Grid<_>>();
+    let heightmap = Grid::parse_with(&input, |c| c.to_digit(10)).unwrap();
 
     // Part A: Find all of the low points on your heightmap. What is the sum of the risk levels of
     // all low points on your heightmap?
@@ -62,12 +61,16 @@ pub fn solve(input: String) -> Solution {
     solution.set_part_a(risk);
 
     // Part B: What do you get if you multiply together the sizes of the three largest basins?
-    let mut basins = lowpoints
+    let basins = basins(&heightmap);
+    image::export("aoc2021_day9_basins", || basins_image(&heightmap, &basins));
+    let mut sizes = basins
+        .regions
         .iter()
-        .map(|&(i, j, _)| basin_size(&heightmap, i, j))
+        .filter(|region| is_basin(&heightmap, region))
+        .map(|region| region.area)
         .collect::<Vec<_>>();
-    basins.sort();
-    let size = basins.iter().rev().take(3).product::<usize>();
+    sizes.sort();
+    let size = sizes.iter().rev().take(3).product::<usize>();
     solution.set_part_b(size);
 
     solution
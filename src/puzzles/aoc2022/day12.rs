@@ -7,9 +7,6 @@ use crate::types::Grid;
 
 use log::debug;
 
-use std::cmp;
-use std::collections::HashSet;
-
 const MAX_HEIGHT: i64 = 25;
 
 fn elevation(c: char) -> i64 {
@@ -22,24 +19,6 @@ fn elevation(c: char) -> i64 {
     }
 }
 
-fn search_is_done(
-    destination: (usize, usize),
-    distances: &Grid<i64>,
-    unvisited_set: &HashSet<(usize, usize)>,
-) -> bool {
-    // iterate until the top has been visited or the smallest tentative
-    // distance in the unvisited set is infinity
-    // also terminate if the unvisited set is empty
-    unvisited_set.is_empty()
-        || !unvisited_set.contains(&destination)
-        || unvisited_set
-            .iter()
-            .map(|&(i, j)| *distances.get(i, j))
-            .min()
-            .unwrap_or(i64::MAX)
-            == i64::MAX
-}
-
 fn is_reachable(
     heightmap: &Grid<i64>,
     current: (usize, usize),
@@ -50,60 +29,13 @@ fn is_reachable(
     height_curr - height_dest <= 1
 }
 
-fn dijkstra(heightmap: &Grid<i64>) -> Grid<i64> {
-    let bottom = heightmap.find(&0).unwrap();
-    let mut unvisited_set = HashSet::new();
-    for (i, j, _) in heightmap.iter_grid() {
-        unvisited_set.insert((i, j));
-    }
-
-    // set all tentative distances to infinity and set the top to 0
-    let mut current = heightmap.find(&MAX_HEIGHT).unwrap();
-    let mut distances = Grid::from(vec![vec![i64::MAX; heightmap.width]; heightmap.height]);
-    distances.set(current.0, current.1, 0);
-
-    // iterate until the bottom has been visited or the smallest tentative
-    // distance in the unvisited set is infinity
-    while !search_is_done(bottom, &distances, &unvisited_set) {
+fn distances_to_top(heightmap: &Grid<i64>) -> Grid<Option<usize>> {
+    // search backwards from the top, so the distance to every position is found at once
+    let top = heightmap.find(&MAX_HEIGHT).unwrap();
+    heightmap.distances([top], |current, next| {
         debug!("visiting node {:?}", current);
-        let distance = *distances.get(current.0, current.1);
-        // consider all unvisited neighbors
-        for node in heightmap
-            .neighbors(current.0, current.1)
-            .into_iter()
-            .filter(|n| is_reachable(heightmap, current, *n) && unvisited_set.contains(n))
-        {
-            let (ii, jj) = node;
-            // calculate their tentative distance thru the current node
-            let node_distance = *distances.get(ii, jj);
-            let new_distance = distance + 1;
-            distances.set(ii, jj, cmp::min(node_distance, new_distance));
-        }
-        // remove the current node from the unvisited set
-        unvisited_set.remove(&current);
-        // select the unvisited node with the smallest tentative distance
-        if let Some(node) = next_node(&unvisited_set, &distances) {
-            current = node;
-        }
-    }
-
-    distances
-}
-
-fn next_node(
-    unvisited_set: &HashSet<(usize, usize)>,
-    distances: &Grid<i64>,
-) -> Option<(usize, usize)> {
-    // select the unvisited node with the smallest tentative distance
-    if let Some((point, _)) = unvisited_set
-        .iter()
-        .map(|&(i, j)| ((i, j), distances.get(i, j)))
-        .min_by(|(_, da), (_, db)| da.cmp(db))
-    {
-        Some(point)
-    } else {
-        None
-    }
+        is_reachable(heightmap, current, next)
+    })
 }
 
 pub fn solve(input: String) -> Solution {
@@ -113,20 +45,19 @@ pub fn solve(input: String) -> Solution {
         .map(|line| line.chars().map(elevation))
         .collect::<Grid<_>>();
     // calculate the distances to the top
-    let distances = dijkstra(&heightmap);
+    let distances = distances_to_top(&heightmap);
 
     // Part A: What is the fewest steps required to move from your current position to the location
     // that should get the best signal?
-    let (i, j) = heightmap.find(&0).unwrap();
-    let best_path_from_start = distances.get(i, j);
-    solution.set_part_a(best_path_from_start);
+    let start = heightmap.find(&0).unwrap();
+    solution.maybe_set_part_a(*distances.get(start.0, start.1));
 
     // Part B: What is the fewest steps required to move starting from any square with elevation a
     // to the location that should get the best signal?
     let best_path_from_bottom = heightmap
         .iter_grid()
         .filter(|&(_, _, &h)| h == 0)
-        .map(|(i, j, _)| distances.get(i, j))
+        .filter_map(|(i, j, _)| *distances.get(i, j))
         .min()
         .unwrap();
     solution.set_part_b(best_path_from_bottom);
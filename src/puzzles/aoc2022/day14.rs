@@ -3,12 +3,12 @@
 */
 
 use super::Solution;
-use crate::types::Point;
+use crate::types::{Grid, Point, SparseGrid};
+use crate::visualize;
 
 use log::debug;
 
 use std::cmp;
-use std::collections::HashMap;
 
 const FLOOR_MARGIN: i64 = 256;
 
@@ -31,6 +31,7 @@ impl From<&str> for RockPath {
 
 #[derive(Clone, PartialEq)]
 enum Material {
+    Air,
     Rock,
     Sand,
 }
@@ -44,55 +45,24 @@ enum SandState {
 }
 
 struct CaveState {
-    // maps positions in the cave to the material that occupies them
-    state: HashMap<Point, Material>,
+    // maps positions in the cave to the material that occupies them; rock is placed before any
+    // sand, and sand only comes to rest above rock, so the bounds of the state are those of the rock
+    state: SparseGrid<Material>,
     sand: Option<Point>,
     sand_state: SandState,
-    lowest_rock: i64,
-    leftmost_rock: i64,
-    rightmost_rock: i64,
 }
 
 impl CaveState {
     fn new() -> Self {
         Self {
-            state: HashMap::new(),
+            state: SparseGrid::new(Material::Air),
             sand: None,
             sand_state: SandState::NotSpawned,
-            lowest_rock: 0,
-            leftmost_rock: 0,
-            rightmost_rock: 0,
         }
     }
 
-    fn set_lowest_rock(&mut self) {
-        let (lowest, _) = self
-            .state
-            .iter()
-            .filter(|(_, m)| m == &&Material::Rock)
-            .max_by(|(pa, _), (pb, _)| pa.y.cmp(&pb.y))
-            .unwrap();
-        self.lowest_rock = lowest.y;
-    }
-
-    fn set_leftmost_rock(&mut self) {
-        let (leftmost, _) = self
-            .state
-            .iter()
-            .filter(|(_, m)| m == &&Material::Rock)
-            .min_by(|(pa, _), (pb, _)| pa.x.cmp(&pb.x))
-            .unwrap();
-        self.leftmost_rock = leftmost.x;
-    }
-
-    fn set_rightmost_rock(&mut self) {
-        let (rightmost, _) = self
-            .state
-            .iter()
-            .filter(|(_, m)| m == &&Material::Rock)
-            .max_by(|(pa, _), (pb, _)| pa.x.cmp(&pb.x))
-            .unwrap();
-        self.rightmost_rock = rightmost.x;
+    fn lowest_rock(&self) -> i64 {
+        self.state.bounds().map_or(0, |(_, max)| max.y)
     }
 
     fn add_rock_path(&mut self, path: RockPath) {
@@ -105,21 +75,17 @@ impl CaveState {
                 let y1 = cmp::max(pa.y, pb.y);
                 for y in y0..=y1 {
                     let p = Point::new(pa.x, y);
-                    self.state.insert(p, Material::Rock);
+                    self.state.set(p, Material::Rock);
                 }
             } else if pa.y == pb.y {
                 let x0 = cmp::min(pa.x, pb.x);
                 let x1 = cmp::max(pa.x, pb.x);
                 for x in x0..=x1 {
                     let p = Point::new(x, pa.y);
-                    self.state.insert(p, Material::Rock);
+                    self.state.set(p, Material::Rock);
                 }
             }
         }
-        // set the lowest/leftmost/rightmost point of rock
-        self.set_lowest_rock();
-        self.set_leftmost_rock();
-        self.set_rightmost_rock();
     }
 
     fn sand_origin() -> Point {
@@ -132,7 +98,7 @@ impl CaveState {
     }
 
     fn is_air(&self, point: &Point) -> bool {
-        !self.state.contains_key(point)
+        !self.state.contains(point)
     }
 
     fn move_sand(&mut self) {
@@ -151,12 +117,12 @@ impl CaveState {
                 self.sand = Some(diag_right);
             } else {
                 // sand has come to rest, add the particle to the final state
-                self.state.insert(point, Material::Sand);
+                self.state.set(point, Material::Sand);
                 self.sand_state = SandState::AtRest;
             }
             // check if the sand has fallen into the void
             if let Some(point) = self.sand
-                && point.y > self.lowest_rock
+                && point.y > self.lowest_rock()
             {
                 debug!("sand has fallen into the void at {}", point);
                 self.sand_state = SandState::InTheVoid;
@@ -179,11 +145,24 @@ impl CaveState {
         }
     }
 
+    fn to_frame(&self) -> Grid<char> {
+        let materials = self.state.iter().map(|(&point, material)| match material {
+            Material::Air => (point, '.'),
+            Material::Rock => (point, '#'),
+            Material::Sand => (point, 'o'),
+        });
+        let origin = [(Self::sand_origin(), '+')];
+        visualize::points_frame(materials.chain(origin), '.')
+    }
+
     fn run_to_completion(&mut self) {
         let origin = Self::sand_origin();
         // run cycles until the sand has fallen into the void
         while self.sand_state != SandState::InTheVoid {
             self.run_cycle();
+            if self.sand_state == SandState::AtRest {
+                visualize::frame(|| self.to_frame());
+            }
             // also terminate if sand has piled up to the origin point
             if self.sand_state == SandState::AtRest && self.sand == Some(origin) {
                 debug!("sand has come to rest at the origin");
@@ -200,16 +179,13 @@ impl CaveState {
     }
 
     fn add_floor(&mut self) {
-        let y = self.lowest_rock + 2;
-        let x0 = self.leftmost_rock - FLOOR_MARGIN;
-        let x1 = self.rightmost_rock + FLOOR_MARGIN;
-        for x in x0..=x1 {
-            let p = Point::new(x, y);
-            self.state.insert(p, Material::Rock);
+        let Some((min, max)) = self.state.bounds() else {
+            return;
+        };
+        let y = max.y + 2;
+        for x in (min.x - FLOOR_MARGIN)..=(max.x + FLOOR_MARGIN) {
+            self.state.set(Point::new(x, y), Material::Rock);
         }
-        self.lowest_rock = y;
-        self.leftmost_rock = x0;
-        self.rightmost_rock = x1;
     }
 }
 
@@ -3,11 +3,11 @@
 */
 
 use super::Solution;
-use crate::types::{Point, RangeInclusive};
+use crate::types::{IntervalSet, Point};
 use crate::utils;
 
-use std::cmp;
 use std::collections::HashSet;
+use std::ops::Range;
 
 #[derive(Debug)]
 struct Sensor {
@@ -28,7 +28,7 @@ impl Sensor {
         Point::new(x, y)
     }
 
-    fn visible_range_of_row(&self, y: i64) -> RangeInclusive<i64> {
+    fn visible_range_of_row(&self, y: i64) -> Range<i64> {
         let max_y = if y < self.pos.y {
             self.pos.y - self.beacon_distance
         } else {
@@ -37,7 +37,7 @@ impl Sensor {
         let y_dist = (max_y - y).abs();
         let x_min = self.pos.x - y_dist;
         let x_max = self.pos.x + y_dist;
-        RangeInclusive::new(x_min, x_max)
+        x_min..(x_max + 1)
     }
 }
 
@@ -61,51 +61,35 @@ fn filter_sensors_by_y_view(sensors: &[Sensor], y: i64) -> impl Iterator<Item =
         .filter(move |s| y >= s.pos.y - s.beacon_distance && y <= s.pos.y + s.beacon_distance)
 }
 
-fn get_visible_x_range_of_row(sensors: &[Sensor], y: i64) -> RangeInclusive<i64> {
-    let mut x_min = i64::MAX;
-    let mut x_max = i64::MIN;
-    // grab all sensors that can view the target row
-    for sensor in filter_sensors_by_y_view(sensors, y) {
-        let x_range = sensor.visible_range_of_row(y);
-        x_min = cmp::min(x_min, x_range.start);
-        x_max = cmp::max(x_max, x_range.end);
-    }
-    RangeInclusive::new(x_min, x_max)
+fn visible_x_ranges_of_row(sensors: &[Sensor], y: i64) -> IntervalSet<i64> {
+    filter_sensors_by_y_view(sensors, y)
+        .map(|s| s.visible_range_of_row(y))
+        .collect()
 }
 
 fn non_beacon_points_in_row(sensors: &[Sensor], beacons: &HashSet<Point>, y: i64) -> i64 {
-    // from experimentation, this is a continuous row so iterate over the
-    // sensors to find the furthest leftmost/rightmost reaches of the range
-    let x_range = get_visible_x_range_of_row(sensors, y);
+    // combine the ranges each sensor can see along the row
+    let x_ranges = visible_x_ranges_of_row(sensors, y);
     // then remove any beacons from the set
     let beacons_in_row = beacons
         .iter()
-        .filter(|b| b.y == y && b.x >= x_range.start && b.x <= x_range.end)
+        .filter(|b| b.y == y && x_ranges.contains(&b.x))
         .count() as i64;
-    x_range.size() - beacons_in_row + 1
+    x_ranges.len() - beacons_in_row
 }
 
 fn find_distress_beacon(sensors: &[Sensor]) -> Option<Point> {
     // check the visible range of each row and search for a single point gap
     for y in 0..=4000000 {
-        // grab all sensors that can view this row
-        let row_sensors = filter_sensors_by_y_view(sensors, y).collect::<Vec<_>>();
-        // there must be at least 2 sensors that can view the row in order for
-        // it to contain the distress beacon
-        if row_sensors.len() < 2 {
-            continue;
-        }
-        // get the visibility ranges of the sensors across the x-axis
-        let sensor_x_ranges = row_sensors
-            .iter()
-            .map(|s| s.visible_range_of_row(y))
-            .collect::<Vec<_>>();
-        // and reduce the ranges
-        let sensors_x_range = RangeInclusive::reduce(sensor_x_ranges);
+        // get the combined visibility ranges of the sensors across the x-axis
+        let x_ranges = visible_x_ranges_of_row(sensors, y);
         // we are looking for a single point of separation between 2 ranges
         // if this is found, this is the distress beacon
-        if sensors_x_range.len() == 2 && sensors_x_range[1].start == sensors_x_range[0].end + 2 {
-            return Some(Point::new(sensors_x_range[0].end + 1, y));
+        let mut ranges = x_ranges.iter();
+        if let (Some(left), Some(right), None) = (ranges.next(), ranges.next(), ranges.next())
+            && right.start == left.end + 1
+        {
+            return Some(Point::new(left.end, y));
         }
     }
     // the distress beacon was not found
@@ -3,246 +3,124 @@
 */
 
 use super::Solution;
+use crate::graph::{self, AllPairs};
 use crate::itertools::*;
+use crate::types::Graph;
 
 use log::debug;
 
 use std::cmp;
-use std::collections::{HashMap, HashSet};
-
-const CHAR_BASE: u16 = 'A' as u16;
-// there are 26 letters, this requires 5 bits per letter
-// this means we need 10 bits per valve
-// this ends up with 1024 options
-const VALVE_BUF_SIZE: usize = 1 << 10;
-// valves are connected to at most 5 other valves
-const MAX_CONNECTIONS: usize = 5;
-
-// NOTE: converted Valve to an integer-struct to avoid lifetime complications
-// valves are 2-letter string identifiers: the first letter is the upper 5 bits
-// and the second letter is the lower 5 bits
-#[derive(Clone, Copy, Eq, Hash, PartialEq)]
-struct Valve(u16);
-
-impl From<&str> for Valve {
-    fn from(value: &str) -> Self {
-        let ca = value.chars().next().unwrap();
-        let cb = value.chars().nth(1).unwrap();
-        let a = (ca as u16) - CHAR_BASE;
-        let b = (cb as u16) - CHAR_BASE;
-        Self(((a & 0x1F) << 5) | (b & 0x1F))
-    }
-}
-
-impl std::fmt::Display for Valve {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let a = ((self.0 >> 5) & 0x1F) + CHAR_BASE;
-        let b = (self.0 & 0x1F) + CHAR_BASE;
-        let ca = char::from_u32(a as u32).unwrap();
-        let cb = char::from_u32(b as u32).unwrap();
-        write!(f, "{}{}", ca, cb)
-    }
-}
-
-// maps valve IDs to their flow rates
-// this array is 8 KiB so stick it on the heap
-struct FlowRates(Vec<u64>);
-
-impl FlowRates {
-    fn new() -> Self {
-        let inner = vec![u64::MAX; VALVE_BUF_SIZE];
-        Self(inner)
-    }
-
-    fn get(&self, vid: u16) -> u64 {
-        self.0[vid as usize]
-    }
-
-    fn set(&mut self, vid: u16, value: u64) {
-        self.0[vid as usize] = value;
-    }
-}
-
-// maps valve IDs to the valve IDs that they are connected to
-// this array is 10 KiB so stick it on the heap
-struct TunnelMap(Vec<Vec<u16>>);
-
-impl TunnelMap {
-    fn new() -> Self {
-        let inner = vec![vec![u16::MAX; MAX_CONNECTIONS]; VALVE_BUF_SIZE];
-        Self(inner)
-    }
-
-    fn set(&mut self, vid_i: u16, vid_j: u16, value: u16) {
-        self.0[vid_i as usize][vid_j as usize] = value;
-    }
-
-    fn connections(&self, vid: u16) -> impl Iterator<Item = &u16> {
-        self.0[vid as usize].iter().take_while(|&&v| v != u16::MAX)
-    }
-}
-
-// 2-D array that stores the distances between pairs of valve IDs
-// this array is at least 1 MiB (depending on usize) so stick it on the heap
-#[derive(Clone)]
-struct Distances(Vec<Vec<u64>>);
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-impl Distances {
-    fn new() -> Self {
-        let inner = vec![vec![u64::MAX; VALVE_BUF_SIZE]; VALVE_BUF_SIZE];
-        Self(inner)
-    }
-
-    fn get(&self, vid_a: u16, vid_b: u16) -> u64 {
-        self.0[vid_a as usize][vid_b as usize]
-    }
+// valves hold their flow rates and are connected by tunnels; the start valve AA is interned
+// first so that it always has id 0
+type ValveGraph = Graph<u64, ()>;
 
-    fn set(&mut self, vid_a: u16, vid_b: u16, value: u64) {
-        self.0[vid_a as usize][vid_b as usize] = value;
-    }
-}
+// valves connected by routes through 0-flow valves, weighted by their length, with the same ids
+// as the valve graph
+type TunnelGraph = Graph<(), u64>;
 
 struct VolcanoInfo {
-    flow_rates: FlowRates,
-    distances: Distances,
+    valves: ValveGraph,
+    distances: AllPairs<u64>,
 }
 
 impl VolcanoInfo {
-    fn new(flow_rates: FlowRates, distances: Distances) -> Self {
-        Self {
-            flow_rates,
-            distances,
-        }
+    fn new(valves: ValveGraph, distances: AllPairs<u64>) -> Self {
+        Self { valves, distances }
     }
 
-    fn flow_rate(&self, valve: u16) -> u64 {
-        self.flow_rates.get(valve)
+    fn flow_rate(&self, valve: usize) -> u64 {
+        *self.valves.node(valve)
     }
 
-    fn distance(&self, from: u16, to: u16) -> u64 {
-        self.distances.get(from, to)
+    fn distance(&self, from: usize, to: usize) -> u64 {
+        self.distances.get(from, to).unwrap_or(u64::MAX)
     }
-}
 
-fn parse_flow_rates(input: &str) -> FlowRates {
-    debug!("parsing valve flow rates");
-    let mut flow_rates = FlowRates::new();
-    for line in input.split('\n') {
-        let valve = Valve::from(&line[6..8]);
-        let flow_end = line.find(';').unwrap();
-        let flow = line[23..flow_end].parse().unwrap();
-        flow_rates.set(valve.0, flow);
+    // the valves which are worth opening
+    fn flowing_valves(&self) -> impl Iterator<Item = usize> + '_ {
+        self.valves.ids().filter(|&vid| self.flow_rate(vid) != 0)
     }
-    flow_rates
 }
 
-fn parse_tunnel_map(input: &str) -> TunnelMap {
-    debug!("parsing tunnel map");
-    let mut tunnel_map = TunnelMap::new();
+fn parse_valves(input: &str) -> ValveGraph {
+    debug!("parsing valve flow rates and tunnels");
+    let mut valves = ValveGraph::new();
+    valves.intern("AA");
     for line in input.split('\n') {
-        let valve = Valve::from(&line[6..8]);
+        let valve = valves.intern(&line[6..8]);
         let flow_end = line.find(';').unwrap();
+        *valves.node_mut(valve) = line[23..flow_end].parse().unwrap();
         // note: valve vs. valves for plural
         let offset = if line.contains("valves") { 25 } else { 24 };
-        for (i, v) in line[(flow_end + offset)..]
-            .split(", ")
-            .map(Valve::from)
-            .enumerate()
-        {
-            tunnel_map.set(valve.0, i as u16, v.0);
+        for next in line[(flow_end + offset)..].split(", ") {
+            let next = valves.intern(next);
+            valves.add_edge(valve, next, ());
         }
     }
-    tunnel_map
+    valves
 }
 
 fn add_valve_connected_nodes(
-    flow_rates: &FlowRates,
-    tunnel_map: &TunnelMap,
-    distances: &mut Distances,
-    from: u16,
-    to: u16,
-    prev: u16,
+    valves: &ValveGraph,
+    tunnels: &mut TunnelGraph,
+    from: usize,
+    to: usize,
+    prev: usize,
     distance: u64,
 ) {
     // look at all connected valves
-    for &vid in tunnel_map.connections(to) {
+    for vid in valves.neighbors(to) {
         // no loopbacks
         if vid == from || vid == prev {
             continue;
         }
         // compress 0-flow nodes (except for AA)
-        if flow_rates.get(vid) == 0 && vid != 0 {
-            add_valve_connected_nodes(
-                flow_rates,
-                tunnel_map,
-                distances,
-                from,
-                vid,
-                to,
-                distance + 1,
-            );
+        if *valves.node(vid) == 0 && vid != 0 {
+            add_valve_connected_nodes(valves, tunnels, from, vid, to, distance + 1);
         } else {
-            distances.set(from, vid, distance);
+            tunnels.add_edge(from, vid, distance);
         }
     }
 }
 
-fn get_valve_graph(flow_rates: &FlowRates, tunnel_map: &TunnelMap) -> Distances {
+fn get_valve_graph(valves: &ValveGraph) -> TunnelGraph {
     debug!("compressing valve graph to remove 0-flow nodes");
-    let mut distances = Distances::new();
+    let mut tunnels = TunnelGraph::new();
+    for vid in valves.ids() {
+        tunnels.intern(valves.label(vid));
+    }
 
     // loop thru all valves
-    for (vid, &flow_rate) in flow_rates.0.iter().enumerate() {
-        let vid = vid as u16;
+    for vid in valves.ids() {
         // skip valves with 0 flow (except for AA since it is the start node)
-        if flow_rate == u64::MAX || (flow_rate == 0 && vid != 0) {
+        if *valves.node(vid) == 0 && vid != 0 {
             continue;
         }
-        // add the self-connection
-        distances.set(vid, vid, 0);
-        debug!("adding connected nodes for valve {}", Valve(vid));
-        for &v in tunnel_map.connections(vid) {
+        debug!("adding connected nodes for valve {}", valves.label(vid));
+        for v in valves.neighbors(vid) {
             // compress 0-flow nodes (except for AA)
-            if flow_rates.get(v) == 0 && v != 0 {
-                add_valve_connected_nodes(flow_rates, tunnel_map, &mut distances, vid, v, vid, 2);
+            if *valves.node(v) == 0 && v != 0 {
+                add_valve_connected_nodes(valves, &mut tunnels, vid, v, vid, 2);
             } else {
-                distances.set(vid, v, 1);
+                tunnels.add_edge(vid, v, 1);
             }
         }
     }
 
-    distances
-}
-
-fn floyd_warshall(distances: &mut Distances) {
-    for k in 0..(VALVE_BUF_SIZE as u16) {
-        for i in 0..(VALVE_BUF_SIZE as u16) {
-            let dik = distances.get(i, k);
-            if dik == u64::MAX {
-                continue;
-            }
-            for j in 0..(VALVE_BUF_SIZE as u16) {
-                let dij = distances.get(i, j);
-                let dkj = distances.get(k, j);
-                if dkj == u64::MAX {
-                    continue;
-                }
-                if dij > dik + dkj {
-                    distances.set(i, j, dik + dkj);
-                }
-            }
-        }
-    }
+    tunnels
 }
 
-fn valve_heuristic(info: &VolcanoInfo, target: u16, from: u16) -> i64 {
+fn valve_heuristic(info: &VolcanoInfo, target: usize, from: usize) -> i64 {
     info.flow_rate(target) as i64 - info.distance(from, target) as i64
 }
 
 fn find_max_pressure_release_rec(
     info: &VolcanoInfo,
-    mut open_valves: HashMap<u16, bool>,
-    valve: u16,
+    mut open_valves: HashMap<usize, bool>,
+    valve: usize,
     mut time: u64,
     mut flow_rate: u64,
     mut flow_volume: u64,
@@ -322,28 +200,17 @@ fn find_max_pressure_release_rec(
 
 fn find_max_pressure_release(info: &VolcanoInfo) -> u64 {
     let mut open_valves = info
-        .flow_rates
-        .0
-        .iter()
-        .enumerate()
-        .filter(|&(_, &flow)| flow != 0 && flow != u64::MAX)
-        .map(|(vid, _)| (vid as u16, false))
+        .flowing_valves()
+        .map(|vid| (vid, false))
         .collect::<HashMap<_, _>>();
     open_valves.insert(0, true);
 
     find_max_pressure_release_rec(info, open_valves, 0, 1, 0, 0, 30)
 }
 
-fn generate_valve_partitions(info: &VolcanoInfo) -> Vec<(HashSet<u16>, HashSet<u16>)> {
+fn generate_valve_partitions(info: &VolcanoInfo) -> Vec<(HashSet<usize>, HashSet<usize>)> {
     // first gather the non-zero flow valves
-    let mut valves = info
-        .flow_rates
-        .0
-        .iter()
-        .enumerate()
-        .filter(|&(_, &flow)| flow != 0 && flow != u64::MAX)
-        .map(|(vid, _)| vid as u16)
-        .collect::<Vec<_>>();
+    let mut valves = info.flowing_valves().collect::<Vec<_>>();
     valves.sort();
     let valves_set = HashSet::<_>::from_iter(valves.clone());
     let n_valves = valves.len();
@@ -362,7 +229,7 @@ fn generate_valve_partitions(info: &VolcanoInfo) -> Vec<(HashSet<u16>, HashSet<u
         } else {
             // for combo in valves.clone().into_iter().combinations(n) {
             for combo in valves.clone().into_iter().combinations(n) {
-                let a = HashSet::<_>::from_iter(combo.into_iter());
+                let a = HashSet::<_>::from_iter(combo);
                 let b = valves_set.difference(&a).copied().collect();
                 partitions.push((a, b));
             }
@@ -373,15 +240,10 @@ fn generate_valve_partitions(info: &VolcanoInfo) -> Vec<(HashSet<u16>, HashSet<u
 }
 
 fn count_valves(info: &VolcanoInfo) -> usize {
-    info.flow_rates
-        .0
-        .iter()
-        .enumerate()
-        .filter(|&(_, &flow)| flow != 0 && flow != u64::MAX)
-        .count()
+    info.flowing_valves().count()
 }
 
-fn get_max_pressure_release_from_valve_set(info: &VolcanoInfo, valve_set: HashSet<u16>) -> u64 {
+fn get_max_pressure_release_from_valve_set(info: &VolcanoInfo, valve_set: HashSet<usize>) -> u64 {
     let mut open_valves = valve_set
         .into_iter()
         .map(|vid| (vid, false))
@@ -417,18 +279,88 @@ fn find_max_pressure_release_with_elephant(info: &VolcanoInfo) -> u64 {
     max_pressure
 }
 
+fn tunnel_distances(valves: &ValveGraph, from: usize) -> HashMap<usize, u64> {
+    let mut distances = HashMap::from([(from, 0)]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(vid) = queue.pop_front() {
+        let distance = distances[&vid];
+        for next in valves.neighbors(vid) {
+            if let Entry::Vacant(entry) = distances.entry(next) {
+                entry.insert(distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+fn best_pressure_by_valve_set_rec(
+    valves: &[(usize, u64)],
+    distances: &[Vec<u64>],
+    current: usize,
+    time_left: u64,
+    opened: usize,
+    pressure: u64,
+    best: &mut [u64],
+) {
+    best[opened] = cmp::max(best[opened], pressure);
+    for (i, &(_, flow_rate)) in valves.iter().enumerate() {
+        // travel to the valve and spend a minute opening it
+        let cost = distances[current][i] + 1;
+        if opened & (1 << i) != 0 || cost >= time_left {
+            continue;
+        }
+        let remaining = time_left - cost;
+        best_pressure_by_valve_set_rec(
+            valves,
+            distances,
+            i,
+            remaining,
+            opened | (1 << i),
+            pressure + flow_rate * remaining,
+            best,
+        );
+    }
+}
+
+fn best_pressure_by_valve_set(valve_graph: &ValveGraph, time_limit: u64) -> Vec<u64> {
+    // the non-zero flow valves, with the start valve AA appended as the final entry
+    let valves = valve_graph
+        .ids()
+        .map(|vid| (vid, *valve_graph.node(vid)))
+        .filter(|&(_, flow)| flow != 0)
+        .collect::<Vec<_>>();
+    let start = valves.len();
+    let distances = valves
+        .iter()
+        .map(|&(vid, _)| vid)
+        .chain([0])
+        .map(|from| {
+            let from_distances = tunnel_distances(valve_graph, from);
+            valves
+                .iter()
+                .map(|(to, _)| from_distances.get(to).copied().unwrap_or(u64::MAX - 1))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // the most pressure that can be released by opening exactly each set of valves
+    let mut best = vec![0; 1 << valves.len()];
+    best_pressure_by_valve_set_rec(&valves, &distances, start, time_limit, 0, 0, &mut best);
+    best
+}
+
 pub fn solve(input: String) -> Solution {
     let mut solution = Solution::new();
-    // parse the valve flow rates and the tunnel map
-    let flow_rates = parse_flow_rates(&input);
-    let tunnel_map = parse_tunnel_map(&input);
+    // parse the valve flow rates and the tunnels between them
+    let valves = parse_valves(&input);
     // then calculate the distances between valves, first compressing the graph
     // to remove the zero-flow nodes
-    let mut distances = get_valve_graph(&flow_rates, &tunnel_map);
-    floyd_warshall(&mut distances);
+    let tunnels = get_valve_graph(&valves);
+    let distances = graph::floyd_warshall(&tunnels, |&distance| distance);
 
     // package the info into a single struct
-    let info = VolcanoInfo::new(flow_rates, distances);
+    let info = VolcanoInfo::new(valves, distances);
 
     // Part A: Work out the steps to release the most pressure in 30 minutes. What is the most
     // pressure you can release?
@@ -442,3 +374,34 @@ pub fn solve(input: String) -> Solution {
 
     solution
 }
+
+pub fn solve_reference(input: String) -> Solution {
+    let mut solution = Solution::new();
+    let valves = parse_valves(&input);
+
+    // Part A: exhaustively search every order of opening valves
+    let best = best_pressure_by_valve_set(&valves, 30);
+    solution.set_part_a(best.into_iter().max().unwrap());
+
+    // Part B: you and the elephant open disjoint sets of valves, so consider every set for the
+    // elephant alongside the best of all subsets of the remaining valves for you
+    let best = best_pressure_by_valve_set(&valves, 26);
+    let full = best.len() - 1;
+    let mut best_subset = best.clone();
+    for bit in 0..full.count_ones() {
+        for set in 0..best_subset.len() {
+            if set & (1 << bit) != 0 {
+                best_subset[set] = cmp::max(best_subset[set], best_subset[set ^ (1 << bit)]);
+            }
+        }
+    }
+    let max_pressure = best
+        .iter()
+        .enumerate()
+        .map(|(set, pressure)| pressure + best_subset[full ^ set])
+        .max()
+        .unwrap();
+    solution.set_part_b(max_pressure);
+
+    solution
+}
@@ -116,10 +116,7 @@ fn scenic_score(heights: &Grid<u32>, i: usize, j: usize) -> u64 {
 
 pub fn solve(input: String) -> Solution {
     let mut solution = Solution::new();
-    let tree_heights = input
-        .split('\n')
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()))
-        .collect::<Grid<_>>();
+    let tree_heights = Grid::parse_with(&input, |c| c.to_digit(10)).unwrap();
 
     // Part A: Consider your map; how many trees are visible from outside the grid?
     let visible = tree_heights
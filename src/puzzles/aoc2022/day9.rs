@@ -3,7 +3,8 @@
 */
 
 use super::Solution;
-use crate::types::{Direction, Point};
+use crate::types::{Grid, Octile, Point};
+use crate::visualize;
 
 use log::debug;
 
@@ -13,24 +14,37 @@ const N_KNOTS: usize = 10;
 
 #[derive(Debug)]
 struct Motion {
-    direction: Direction,
+    direction: Octile,
     length: i64,
 }
 
 impl From<&str> for Motion {
     fn from(value: &str) -> Self {
-        let direction = match value.chars().next().unwrap() {
-            'U' => Direction::North,
-            'D' => Direction::South,
-            'L' => Direction::West,
-            'R' => Direction::East,
-            _ => unreachable!(),
-        };
+        let direction = Octile::try_from(value.chars().next().unwrap()).unwrap();
         let length = value[2..].parse().unwrap();
         Self { direction, length }
     }
 }
 
+// draws the visited positions and knots, flipping y so that north is up
+fn rope_frame(knots: &[Point], tail_positions: &HashSet<Point>) -> Grid<char> {
+    let visited = tail_positions.iter().map(|&p| (p, '#'));
+    let start = [(Point::origin(), 's')];
+    let knots = knots.iter().enumerate().rev().map(|(i, &p)| {
+        let c = if i == 0 {
+            'H'
+        } else {
+            char::from_digit(i as u32, 10).unwrap()
+        };
+        (p, c)
+    });
+    let points = visited
+        .chain(start)
+        .chain(knots)
+        .map(|(p, c)| (Point::new(p.x, -p.y), c));
+    visualize::points_frame(points, '.')
+}
+
 struct Rope {
     head: Point,
     tail: Point,
@@ -50,12 +64,12 @@ impl Rope {
         (self.head.x - self.tail.x).abs() <= 1 && (self.head.y - self.tail.y).abs() <= 1
     }
 
-    fn move_head(&mut self, direction: &Direction) {
+    fn move_head(&mut self, direction: &Octile) {
         match direction {
-            Direction::North => self.head.y += 1,
-            Direction::South => self.head.y -= 1,
-            Direction::West => self.head.x -= 1,
-            Direction::East => self.head.x += 1,
+            Octile::North => self.head.y += 1,
+            Octile::South => self.head.y -= 1,
+            Octile::West => self.head.x -= 1,
+            Octile::East => self.head.x += 1,
             _ => unreachable!(),
         }
     }
@@ -82,6 +96,7 @@ impl Rope {
             debug!("head @ {} tail @ {}", self.head, self.tail);
             // track the new tail position
             self.tail_positions.insert(self.tail);
+            visualize::frame(|| rope_frame(&[self.head, self.tail], &self.tail_positions));
         }
     }
 }
@@ -105,12 +120,12 @@ impl KnottedRope {
         (a.x - b.x).abs() <= 1 && (a.y - b.y).abs() <= 1
     }
 
-    fn move_head(&mut self, direction: &Direction) {
+    fn move_head(&mut self, direction: &Octile) {
         match direction {
-            Direction::North => self.knots[0].y += 1,
-            Direction::South => self.knots[0].y -= 1,
-            Direction::West => self.knots[0].x -= 1,
-            Direction::East => self.knots[0].x += 1,
+            Octile::North => self.knots[0].y += 1,
+            Octile::South => self.knots[0].y -= 1,
+            Octile::West => self.knots[0].x -= 1,
+            Octile::East => self.knots[0].x += 1,
             _ => unreachable!(),
         }
     }
@@ -137,6 +152,7 @@ impl KnottedRope {
             }
             // track the new tail position
             self.tail_positions.insert(self.knots[N_KNOTS - 1]);
+            visualize::frame(|| rope_frame(&self.knots, &self.tail_positions));
         }
     }
 }
@@ -3,11 +3,13 @@
 */
 
 use super::Solution;
-use crate::types::Grid;
+use crate::geometry::{self, Location};
+use crate::image::{self, Image};
+use crate::types::{Grid, Point};
 
 use log::debug;
 
-use std::collections::{HashSet, VecDeque};
+use std::collections::HashSet;
 
 #[derive(Clone, Debug)]
 enum TileType {
@@ -37,9 +39,8 @@ impl From<char> for TileType {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 struct Tile {
-    ty: TileType,
     connects_north: bool,
     connects_south: bool,
     connects_east: bool,
@@ -65,7 +66,6 @@ impl Tile {
             TileType::PipeHorizontal | TileType::Pipe90NW | TileType::Pipe90SW
         );
         Self {
-            ty,
             connects_north,
             connects_south,
             connects_east,
@@ -74,18 +74,6 @@ impl Tile {
     }
 }
 
-impl Default for Tile {
-    fn default() -> Self {
-        Self {
-            ty: TileType::Ground,
-            connects_north: false,
-            connects_south: false,
-            connects_east: false,
-            connects_west: false,
-        }
-    }
-}
-
 struct PipeMap {
     grid: Grid<Tile>,
     start_point: (usize, usize),
@@ -167,151 +155,33 @@ impl From<String> for PipeMap {
     }
 }
 
-fn get_expanded_pipe_map(pipe_map: &PipeMap, points: &[(usize, usize)]) -> PipeMap {
-    // expand 3x
-    let mut grid = Grid::<Tile>::new(pipe_map.grid.width * 3, pipe_map.grid.height * 3);
-    let start_point = (
-        (pipe_map.start_point.0 * 3) + 1,
-        (pipe_map.start_point.1 * 3) + 1,
-    );
-
-    for &(i, j) in points {
-        let tile = pipe_map.grid.get(i, j);
-        match tile.ty {
-            TileType::PipeVertical | TileType::Start
-                if tile.connects_north && tile.connects_south =>
-            {
-                grid.set(i * 3, (j * 3) + 1, Tile::new(TileType::PipeVertical));
-                grid.set((i * 3) + 1, (j * 3) + 1, Tile::new(TileType::PipeVertical));
-                grid.set((i * 3) + 2, (j * 3) + 1, Tile::new(TileType::PipeVertical));
-            }
-            TileType::PipeHorizontal | TileType::Start
-                if tile.connects_east && tile.connects_west =>
-            {
-                grid.set((i * 3) + 1, j * 3, Tile::new(TileType::PipeHorizontal));
-                grid.set(
-                    (i * 3) + 1,
-                    (j * 3) + 1,
-                    Tile::new(TileType::PipeHorizontal),
-                );
-                grid.set(
-                    (i * 3) + 1,
-                    (j * 3) + 2,
-                    Tile::new(TileType::PipeHorizontal),
-                );
-            }
-            TileType::Pipe90NE | TileType::Start if tile.connects_north && tile.connects_east => {
-                grid.set(i * 3, (j * 3) + 1, Tile::new(TileType::PipeVertical));
-                grid.set((i * 3) + 1, (j * 3) + 1, Tile::new(TileType::Pipe90NE));
-                grid.set(
-                    (i * 3) + 1,
-                    (j * 3) + 2,
-                    Tile::new(TileType::PipeHorizontal),
-                );
-            }
-            TileType::Pipe90NW | TileType::Start if tile.connects_north && tile.connects_west => {
-                grid.set(i * 3, (j * 3) + 1, Tile::new(TileType::PipeVertical));
-                grid.set((i * 3) + 1, (j * 3) + 1, Tile::new(TileType::Pipe90NW));
-                grid.set((i * 3) + 1, j * 3, Tile::new(TileType::PipeHorizontal));
-            }
-            TileType::Pipe90SE | TileType::Start if tile.connects_south && tile.connects_east => {
-                grid.set((i * 3) + 2, (j * 3) + 1, Tile::new(TileType::PipeVertical));
-                grid.set((i * 3) + 1, (j * 3) + 1, Tile::new(TileType::Pipe90SE));
-                grid.set(
-                    (i * 3) + 1,
-                    (j * 3) + 2,
-                    Tile::new(TileType::PipeHorizontal),
-                );
-            }
-            TileType::Pipe90SW | TileType::Start if tile.connects_south && tile.connects_west => {
-                grid.set((i * 3) + 2, (j * 3) + 1, Tile::new(TileType::PipeVertical));
-                grid.set((i * 3) + 1, (j * 3) + 1, Tile::new(TileType::Pipe90SW));
-                grid.set((i * 3) + 1, j * 3, Tile::new(TileType::PipeHorizontal));
-            }
-            _ => {}
-        }
-    }
-
-    PipeMap { grid, start_point }
+fn loop_polygon(points: &[(usize, usize)]) -> Vec<Point> {
+    // only the tiles where the loop turns are vertices of the polygon
+    let n = points.len();
+    (0..n)
+        .filter(|&k| {
+            let (prev, next) = (points[(k + n - 1) % n], points[(k + 1) % n]);
+            prev.0 != next.0 && prev.1 != next.1
+        })
+        .map(|k| Point::new_for_grid(points[k].0, points[k].1))
+        .collect()
 }
 
-fn enclosed_area(pipe_map: &PipeMap, points: &[(usize, usize)]) -> usize {
-    // start by expanding the pipe map to a 3x resolution
-    let pipe_map_large = get_expanded_pipe_map(pipe_map, points);
-
-    // then flood fill to mark all points outside the loop
-    let mut filled = Grid::<bool>::new(pipe_map_large.grid.width, pipe_map_large.grid.height);
-    for (i, j, tile) in pipe_map_large.grid.iter_grid() {
-        if !matches!(tile.ty, TileType::Ground) {
-            filled.set(i, j, true);
-        }
-    }
-    let mut queue = VecDeque::new();
-    // start with all edge points
-    for i in 0..pipe_map_large.grid.height {
-        if matches!(pipe_map_large.grid.get(i, 0).ty, TileType::Ground) {
-            queue.push_back((i, 0));
-        }
-        if matches!(
-            pipe_map_large.grid.get(i, pipe_map_large.grid.width - 1).ty,
-            TileType::Ground
-        ) {
-            queue.push_back((i, pipe_map_large.grid.width - 1));
-        }
-    }
-    for j in 1..(pipe_map_large.grid.width - 1) {
-        if matches!(pipe_map_large.grid.get(0, j).ty, TileType::Ground) {
-            queue.push_back((0, j));
-        }
-        if matches!(
-            pipe_map_large
-                .grid
-                .get(pipe_map_large.grid.height - 1, j)
-                .ty,
-            TileType::Ground
-        ) {
-            queue.push_back((pipe_map_large.grid.height - 1, j));
-        }
-    }
-    while let Some((i, j)) = queue.pop_front() {
-        if *filled.get(i, j) {
-            continue;
-        }
-        filled.set(i, j, true);
-        if i > 0 && !filled.get(i - 1, j) {
-            queue.push_back((i - 1, j));
-        }
-        if j > 0 {
-            queue.push_back((i, j - 1));
-        }
-        if i < pipe_map_large.grid.height - 1 {
-            queue.push_back((i + 1, j));
-        }
-        if j < pipe_map_large.grid.width - 1 {
-            queue.push_back((i, j + 1));
-        }
-    }
-
-    // now downsize where the area is each 3x3 grid where all points are unfilled
-    let mut area = 0;
-    for i in 0..pipe_map.grid.height {
-        for j in 0..pipe_map.grid.width {
-            let mut all_unfilled = true;
-            for ii in (i * 3)..((i + 1) * 3) {
-                for jj in (j * 3)..((j + 1) * 3) {
-                    if *filled.get(ii, jj) {
-                        all_unfilled = false;
-                        break;
-                    }
-                }
-            }
-            if all_unfilled {
-                area += 1;
-            }
-        }
-    }
-
-    area
+fn enclosed_image(pipe_map: &PipeMap, polygon: &[Point]) -> Image {
+    // 0 is outside the loop, 1 is the loop itself, and 2 is enclosed by the loop
+    let (width, height) = (pipe_map.grid.width, pipe_map.grid.height);
+    let map = Grid::from_fn(width, height, |i, j| {
+        match geometry::locate(polygon, Point::new_for_grid(i, j)) {
+            Location::Outside => 0u8,
+            Location::Boundary => 1,
+            Location::Inside => 2,
+        }
+    });
+    Image::from_grid(&map, 4, |tile| match tile {
+        1 => [230, 200, 40],
+        2 => [40, 160, 60],
+        _ => [20, 20, 40],
+    })
 }
 
 pub fn solve(input: String) -> Solution {
@@ -329,7 +199,13 @@ pub fn solve(input: String) -> Solution {
 
     // Part B: Figure out whether you have time to search for the nest by calculating the area
     // within the loop. How many tiles are enclosed by the loop?
-    let area = enclosed_area(&pipe_map, &loop_points);
+    // the loop is a polygon through the centers of its tiles, so Pick's theorem gives the tiles
+    // strictly inside it
+    let polygon = loop_polygon(&loop_points);
+    image::export("aoc2023_day10_enclosed", || {
+        enclosed_image(&pipe_map, &polygon)
+    });
+    let area = geometry::interior_points(&polygon);
     solution.set_part_b(area);
 
     solution
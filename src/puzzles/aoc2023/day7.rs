@@ -89,9 +89,9 @@ enum HandType {
 
 impl HandType {
     fn from_card_counts(counter: Counter<&Card>) -> Self {
-        let sorted = counter.sorted().collect::<Vec<_>>();
-        let (_, top_count) = sorted[0];
-        let &(_, next_count) = sorted.get(1).unwrap_or(&(&&Card::Joker, 0));
+        let most_common = counter.most_common(2);
+        let (_, top_count) = most_common[0];
+        let next_count = most_common.get(1).map_or(0, |&(_, count)| count);
         match top_count {
             5 => HandType::FiveOfAKind,
             4 => HandType::FourOfAKind,
@@ -198,8 +198,8 @@ impl JokerHand {
         // check what the best card is
         let top_count = counter.max();
         let mut card_types = counter
-            .sorted()
-            .filter(|&(_, count)| count == top_count)
+            .iter()
+            .filter(|&(_, &count)| count == top_count)
             .map(|(&card_type, _)| card_type.clone())
             .collect::<Vec<_>>();
         card_types.sort();
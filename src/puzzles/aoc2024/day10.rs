@@ -73,10 +73,7 @@ pub fn solve(input: String) -> Solution {
     let mut solution = Solution::new();
     // The reindeer brings you a blank topographic map of the surrounding area. The topographic map
     // indicates the height at each position using a scale from 0 (lowest) to 9 (highest).
-    let map = input
-        .split('\n')
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()))
-        .collect::<Grid<_>>();
+    let map = Grid::parse_with(&input, |c| c.to_digit(10)).unwrap();
     let trailheads = map.find_all(&0);
 
     // Part A: What is the sum of the scores of all trailheads on your topographic map?
@@ -28,7 +28,7 @@ fn blink(stones: &[u64], count: usize) -> usize {
         stone_counts = new_counts;
     }
 
-    stone_counts.iter().map(|(_, &count)| count).sum()
+    stone_counts.total()
 }
 
 pub fn solve(input: String) -> Solution {
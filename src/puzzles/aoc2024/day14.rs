@@ -3,8 +3,10 @@
 */
 
 use super::Solution;
-use crate::types::Point;
+use crate::image::{self, Image};
+use crate::types::{Grid, Point, Topology};
 use crate::utils;
+use crate::visualize;
 
 use log::debug;
 
@@ -20,27 +22,25 @@ struct Robot {
 }
 
 impl Robot {
-    fn move_once(&mut self) {
-        let mut position = self.position + self.velocity;
-        if position.x >= WIDTH {
-            position.x %= WIDTH;
-        } else if position.x < 0 {
-            position.x += WIDTH;
-        }
-        if position.y >= HEIGHT {
-            position.y %= HEIGHT;
-        } else if position.y < 0 {
-            position.y += HEIGHT;
-        }
-        self.position = position;
+    fn move_over_time(&mut self, space: &Grid<()>, time: i64) {
+        // the robots teleport to the opposite edge, so the space wraps around
+        let (i, j) = space
+            .offset(
+                self.position.y as usize,
+                self.position.x as usize,
+                self.velocity.y * time,
+                self.velocity.x * time,
+            )
+            .unwrap();
+        debug!(
+            "robot: p={} v={} moved to {},{}",
+            self.position, self.velocity, j, i
+        );
+        self.position = Point::new(j as i64, i as i64);
     }
 
-    fn move_over_time(&mut self, time: usize) {
-        debug!("robot: p={} v={}", self.position, self.velocity);
-        for _ in 0..time {
-            self.move_once();
-            debug!("moved to {}", self.position);
-        }
+    fn move_once(&mut self, space: &Grid<()>) {
+        self.move_over_time(space, 1);
     }
 }
 
@@ -75,6 +75,14 @@ fn safety_factor(robots: &[Robot]) -> usize {
     quad_a * quad_b * quad_c * quad_d
 }
 
+fn robots_frame(robots: &[Robot]) -> Grid<char> {
+    let mut frame = Grid::from(vec![vec!['.'; WIDTH as usize]; HEIGHT as usize]);
+    for robot in robots {
+        frame.set(robot.position.y as usize, robot.position.x as usize, '#');
+    }
+    frame
+}
+
 fn robots_in_unique_positions(robots: &[Robot]) -> bool {
     let mut positions = HashSet::new();
     for robot in robots {
@@ -91,11 +99,12 @@ pub fn solve(input: String) -> Solution {
     // The area outside the bathroom is swarming with robots! You make a list of all of the robots'
     // current positions and velocities, one robot per line.
     let mut robots = input.split('\n').map(Robot::from).collect::<Vec<_>>();
+    let space = Grid::new(WIDTH as usize, HEIGHT as usize).with_topology(Topology::Toroidal);
 
     // Part A: Predict the motion of the robots in your list within a space which is 101 tiles wide
     // and 103 tiles tall. What will the safety factor be after exactly 100 seconds have elapsed?
     for robot in robots.iter_mut() {
-        robot.move_over_time(100);
+        robot.move_over_time(&space, 100);
     }
     let safety = safety_factor(&robots);
     solution.set_part_a(safety);
@@ -106,10 +115,17 @@ pub fn solve(input: String) -> Solution {
     let mut elapsed = 100;
     while !robots_in_unique_positions(&robots) {
         for robot in robots.iter_mut() {
-            robot.move_once();
+            robot.move_once(&space);
         }
         elapsed += 1;
+        visualize::frame(|| robots_frame(&robots));
     }
+    image::export("aoc2024_day14_tree", || {
+        Image::from_grid(&robots_frame(&robots), 4, |&c| match c {
+            '#' => [40, 180, 60],
+            _ => [10, 10, 30],
+        })
+    });
     solution.set_part_b(elapsed);
 
     solution
@@ -3,8 +3,9 @@
 */
 
 use super::Solution;
-use crate::types::{Direction, Grid};
+use crate::types::{Grid, Octile};
 use crate::utils;
+use crate::visualize;
 
 use log::debug;
 
@@ -38,16 +39,22 @@ impl Map {
         item == 'O' || item == '[' || item == ']'
     }
 
-    fn neighbor(&self, at: (usize, usize), direction: Direction) -> (usize, usize) {
+    fn neighbor(&self, at: (usize, usize), direction: Octile) -> (usize, usize) {
         let (i, j) = at;
         self.grid.neighbor(i, j, direction).unwrap()
     }
 
+    fn to_frame(&self) -> Grid<char> {
+        let mut frame = self.grid.clone();
+        frame.set(self.robot.0, self.robot.1, '@');
+        frame
+    }
+
     fn boxes(&self) -> Vec<(usize, usize)> {
         self.grid.find_all_with(|&c| c == 'O' || c == '[')
     }
 
-    fn move_robot_into_box(&mut self, at: (usize, usize), direction: Direction) {
+    fn move_robot_into_box(&mut self, at: (usize, usize), direction: Octile) {
         let mut point = at;
         while self.is_box(point) {
             point = self.neighbor(point, direction);
@@ -62,27 +69,27 @@ impl Map {
     fn box_adjacent_coordinates(
         &self,
         at: (usize, usize),
-        direction: Direction,
+        direction: Octile,
     ) -> Vec<(usize, usize)> {
         let (i, j) = at;
         match direction {
-            Direction::East => {
+            Octile::East => {
                 vec![(i, j + 2)]
             }
-            Direction::West => {
+            Octile::West => {
                 vec![(i, j - 1)]
             }
-            Direction::North => {
+            Octile::North => {
                 vec![(i - 1, j), (i - 1, j + 1)]
             }
-            Direction::South => {
+            Octile::South => {
                 vec![(i + 1, j), (i + 1, j + 1)]
             }
             _ => unreachable!(),
         }
     }
 
-    fn can_move_robot_into_box_doubled(&self, at: (usize, usize), direction: Direction) -> bool {
+    fn can_move_robot_into_box_doubled(&self, at: (usize, usize), direction: Octile) -> bool {
         let (i, j) = at;
         // always index the box from the left side
         if self.grid.get(i, j) == &']' {
@@ -107,7 +114,7 @@ impl Map {
     fn move_robot_into_box_doubled(
         &mut self,
         at: (usize, usize),
-        direction: Direction,
+        direction: Octile,
         visited: &mut HashSet<(usize, usize)>,
     ) {
         let (i, j) = at;
@@ -146,7 +153,7 @@ impl Map {
         visited.insert(at);
     }
 
-    fn move_robot(&mut self, direction: Direction) {
+    fn move_robot(&mut self, direction: Octile) {
         let next = self.neighbor(self.robot, direction);
         if self.is_empty(next) {
             self.robot = next;
@@ -155,7 +162,7 @@ impl Map {
         }
     }
 
-    fn move_robot_doubled(&mut self, direction: Direction) {
+    fn move_robot_doubled(&mut self, direction: Octile) {
         let next = self.neighbor(self.robot, direction);
         if self.is_empty(next) {
             self.robot = next;
@@ -183,20 +190,12 @@ impl std::fmt::Display for Map {
     }
 }
 
-fn parse_input(input: String) -> (Map, Vec<Direction>) {
+fn parse_input(input: String) -> (Map, Vec<Octile>) {
     let (grid, moves) = utils::split(&input, "\n\n").unwrap();
     let map = Map::new(Grid::from(grid.to_owned()));
     let moves = moves
         .split('\n')
-        .flat_map(|line| {
-            line.chars().map(|c| match c {
-                '^' => Direction::North,
-                'v' => Direction::South,
-                '>' => Direction::East,
-                '<' => Direction::West,
-                _ => unreachable!(),
-            })
-        })
+        .flat_map(|line| line.chars().map(|c| Octile::try_from(c).unwrap()))
         .collect::<Vec<_>>();
     (map, moves)
 }
@@ -204,18 +203,13 @@ fn parse_input(input: String) -> (Map, Vec<Direction>) {
 fn double_map(mut map: Map, robot: (usize, usize)) -> Map {
     map.grid.set(robot.0, robot.1, '@');
 
-    let mut new_grid = Grid::new(map.grid.width * 2, map.grid.height);
-    for (i, j, c) in map.grid.iter_grid() {
-        let (a, b) = match c {
-            '#' => ('#', '#'),
-            'O' => ('[', ']'),
-            '.' => ('.', '.'),
-            '@' => ('@', '.'),
-            _ => unreachable!(),
-        };
-        new_grid.set(i, j * 2, a);
-        new_grid.set(i, (j * 2) + 1, b);
-    }
+    let new_grid = map.grid.widen(|c| match c {
+        '#' => ['#', '#'],
+        'O' => ['[', ']'],
+        '.' => ['.', '.'],
+        '@' => ['@', '.'],
+        _ => unreachable!(),
+    });
     Map::new(new_grid)
 }
 
@@ -232,6 +226,7 @@ pub fn solve(input: String) -> Solution {
     let mut map_a = map.clone();
     for &direction in moves.iter() {
         map_a.move_robot(direction);
+        visualize::frame(|| map_a.to_frame());
     }
     let coordinates = map_a
         .boxes()
@@ -247,6 +242,7 @@ pub fn solve(input: String) -> Solution {
     let mut map_b = double_map(map, start);
     for &direction in moves.iter() {
         map_b.move_robot_doubled(direction);
+        visualize::frame(|| map_b.to_frame());
     }
     let coordinates = map_b
         .boxes()
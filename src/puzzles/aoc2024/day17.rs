@@ -123,6 +123,14 @@ impl Computer {
         }
         false
     }
+
+    fn run(&mut self) -> &[u64] {
+        let mut halt = false;
+        while !halt {
+            halt = self.next();
+        }
+        &self.outputs
+    }
 }
 
 impl From<String> for Computer {
@@ -148,6 +156,9 @@ impl From<String> for Computer {
     }
 }
 
+// the program in the full input, which output below is de-compiled from
+const DECOMPILED_PROGRAM: [u8; 16] = [2, 4, 1, 7, 7, 5, 0, 3, 4, 0, 1, 7, 5, 5, 3, 0];
+
 fn output(a: u64) -> u64 {
     // see below for decompilation
     let mut b = (a & 0x7) ^ 7;
@@ -157,21 +168,35 @@ fn output(a: u64) -> u64 {
     b & 0x7
 }
 
-fn find_quine_rec(computer: &Computer, register_a: u64, program_index: usize) -> Option<u64> {
-    let expected = computer.program[program_index] as u64;
+fn first_output(computer: &Computer, a: u64) -> Option<u64> {
+    let mut computer = computer.clone();
+    computer.registers[0] = a;
+    while computer.outputs.is_empty() {
+        if computer.next() {
+            return None;
+        }
+    }
+    computer.outputs.first().copied()
+}
+
+fn find_quine_rec<F>(computer: &Computer, output: &F, register_a: u64, index: usize) -> Option<u64>
+where
+    F: Fn(u64) -> Option<u64>,
+{
+    let expected = computer.program[index] as u64;
 
     let mut options = Vec::new();
     for a in register_a..(register_a + 8) {
-        if output(a) == expected {
+        if output(a) == Some(expected) {
             options.push(a);
         }
     }
-    if program_index == 0 {
+    if index == 0 {
         options.into_iter().min()
     } else {
         options
             .into_iter()
-            .filter_map(|a| find_quine_rec(computer, a << 3, program_index - 1))
+            .filter_map(|a| find_quine_rec(computer, output, a << 3, index - 1))
             .min()
     }
 }
@@ -200,8 +225,82 @@ fn find_quine(computer: &Computer) -> Option<u64> {
     // By replacing modulo with & 0x7 we see that each loop computes the output using only the
     // value of A (B and C are derived exclusively from A) and that each iteration shifts off the
     // 3 least-significant bits of A. This means that we can work backwards and construct the input
-    // A value recursively:
-    find_quine_rec(computer, 0, computer.program.len() - 1)
+    // A value recursively. Other programs with the same loop shape, such as the sample, are
+    // handled by running a single iteration of the program in place of the de-compiled output.
+    let last = computer.program.len() - 1;
+    let program = computer.program.iter().map(|&opcode| opcode as u8);
+    if program.eq(DECOMPILED_PROGRAM) {
+        find_quine_rec(computer, &|a| Some(output(a)), 0, last)
+    } else {
+        find_quine_rec(computer, &|a| first_output(computer, a), 0, last)
+    }
+}
+
+// whether every read of B or C in one pass through the program comes after a write to it in the
+// same pass, so that nothing carries over from one iteration to the next except A
+fn reads_only_fresh_registers(instructions: &[&[u64]]) -> bool {
+    let mut fresh = [true, false, false];
+    for pair in instructions {
+        let (opcode, operand) = (pair[0], pair[1]);
+        let combo = (4..=6).contains(&operand).then(|| operand as usize - 4);
+        let reads = match opcode {
+            1 => vec![1],
+            4 => vec![1, 2],
+            3 => vec![0],
+            _ => combo.into_iter().collect(),
+        };
+        if reads.iter().any(|&register| !fresh[register]) {
+            return false;
+        }
+        match opcode {
+            1 | 2 | 4 | 6 => fresh[1] = true,
+            7 => fresh[2] = true,
+            _ => {}
+        }
+    }
+    true
+}
+
+/// Searches for the quine by running the real program on each candidate rather than relying on
+/// the de-compiled output function. This shares one assumption with find_quine: that the program
+/// is a single loop which shifts 3 bits off A, jumps back to the start while A is nonzero, and
+/// derives B and C afresh from A each iteration, so that the last outputs depend only on the
+/// most-significant bits of A. That assumption is checked here rather than trusted, so an input
+/// which breaks it fails loudly instead of leading both solvers to the same wrong answer.
+fn find_quine_reference(computer: &Computer) -> Option<u64> {
+    let program = computer
+        .program
+        .iter()
+        .map(|&opcode| opcode as u64)
+        .collect::<Vec<_>>();
+    let instructions = program.chunks(2).collect::<Vec<_>>();
+    let count = |opcode| instructions.iter().filter(|pair| pair[0] == opcode).count();
+    assert!(
+        count(0) == 1
+            && instructions.contains(&[0, 3].as_slice())
+            && count(3) == 1
+            && program.ends_with(&[3, 0])
+            && reads_only_fresh_registers(&instructions),
+        "find_quine_reference: program is not a loop shifting 3 bits off A"
+    );
+
+    // build A 3 bits at a time from the most-significant end, keeping every candidate whose
+    // output matches the end of the program
+    let mut candidates = vec![0];
+    for i in (0..program.len()).rev() {
+        let mut next_candidates = Vec::new();
+        for candidate in candidates.into_iter() {
+            for a in (candidate << 3)..((candidate << 3) + 8) {
+                let mut computer = computer.clone();
+                computer.registers[0] = a;
+                if computer.run() == &program[i..] {
+                    next_candidates.push(a);
+                }
+            }
+        }
+        candidates = next_candidates;
+    }
+    candidates.into_iter().filter(|&a| a > 0).min()
 }
 
 pub fn solve(input: String) -> Solution {
@@ -235,3 +334,24 @@ pub fn solve(input: String) -> Solution {
 
     solution
 }
+
+pub fn solve_reference(input: String) -> Solution {
+    let mut solution = Solution::new();
+    let computer = Computer::from(input);
+
+    // Part A: run the program
+    let output = computer
+        .clone()
+        .run()
+        .iter()
+        .map(|o| o.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    solution.set_part_a(output);
+
+    // Part B: search for the quine by running the program on each candidate
+    let register = find_quine_reference(&computer);
+    solution.maybe_set_part_b(register);
+
+    solution
+}
@@ -3,8 +3,11 @@
 */
 
 use super::Solution;
+use crate::random::{self, Rng};
 use crate::types::{Maze, Point};
 
+use std::cmp;
+
 fn drop_bytes(space: &mut Maze, bytes: &[Point]) {
     for point in bytes {
         space.set(point.y as usize, point.x as usize, '#');
@@ -22,7 +25,7 @@ fn find_blocking_byte(space: Maze, bytes: &[Point]) -> Option<String> {
         drop_bytes(&mut c_space, &bytes[..=c]);
 
         let distances = c_space.distance_matrix((0, 0));
-        if distances.get(space.height - 1, space.width - 1) == &usize::MAX {
+        if distances.get(space.height - 1, space.width - 1).is_none() {
             b = c;
             last_blocked = Some(c);
         } else {
@@ -45,8 +48,8 @@ pub fn solve(input: String) -> Solution {
     // what is the minimum number of steps needed to reach the exit?
     drop_bytes(&mut space, &falling_bytes[..1024]);
     let distances = space.distance_matrix((0, 0));
-    let steps_to_exit = distances.get(space.height - 1, space.width - 1);
-    solution.set_part_a(steps_to_exit);
+    let steps_to_exit = *distances.get(space.height - 1, space.width - 1);
+    solution.maybe_set_part_a(steps_to_exit);
 
     // Part B: Simulate more of the bytes that are about to corrupt your memory space. What are the
     // coordinates of the first byte that will prevent the exit from being reachable from your
@@ -56,3 +59,16 @@ pub fn solve(input: String) -> Solution {
 
     solution
 }
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // the first kilobyte always falls, so size is the number of bytes which fall afterwards; the
+    // start and exit are never corrupted
+    let count = cmp::min(1024 + size.max(1), (71 * 71) - 2);
+    random::points(rng, 71 * 71, 71, 71)
+        .into_iter()
+        .filter(|&p| p != Point::origin() && p != Point::new(70, 70))
+        .take(count)
+        .map(|p| format!("{},{}", p.x, p.y))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
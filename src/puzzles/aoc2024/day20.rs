@@ -3,49 +3,28 @@
 */
 
 use super::Solution;
-use crate::types::{Direction, Maze};
+use crate::random::{self, Rng};
+use crate::search::Search;
+use crate::types::Maze;
 
 use std::cmp;
 use std::collections::HashMap;
 
-fn start_direction(maze: &Maze) -> Option<Direction> {
-    let (i, j) = maze.find(&'S').unwrap();
-    for direction in Direction::cardinal() {
-        let (ii, jj) = maze.neighbor(i, j, direction).unwrap();
-        if !maze.is_wall(ii, jj) {
-            return Some(direction);
-        }
-    }
-    None
-}
-
 fn find_path(maze: &Maze) -> Vec<(usize, usize)> {
+    // the track is a single corridor, so its only path is also the shortest one
     let start = maze.find(&'S').unwrap();
     let end = maze.find(&'E').unwrap();
-    let mut path = Vec::new();
-
-    let mut current = start;
-    let mut direction = start_direction(maze).unwrap();
-    while current != end {
-        path.push(current);
-        let next = maze.neighbor(current.0, current.1, direction).unwrap();
-        if maze.is_wall(next.0, next.1) {
-            let cw = direction.turn_90_clockwise();
-            let cw_next = maze.neighbor(current.0, current.1, cw).unwrap();
-            if maze.is_wall(cw_next.0, cw_next.1) {
-                direction = direction.turn_90_counterclockwise();
-                current = maze.neighbor(current.0, current.1, direction).unwrap();
-            } else {
-                current = cw_next;
-                direction = cw;
-            }
-        } else {
-            current = next;
-        }
-    }
-    path.push(end);
-
-    path
+    Search::new()
+        .start(start)
+        .goal(|&position| position == end)
+        .record_paths()
+        .bfs(|&(i, j)| {
+            maze.neighbors(i, j)
+                .into_iter()
+                .filter(|&(ii, jj)| !maze.is_wall(ii, jj))
+        })
+        .path_to(&end)
+        .unwrap()
 }
 
 fn cheat_candidates(
@@ -61,7 +40,7 @@ fn cheat_candidates(
     let i_start = i.saturating_sub(duration);
     let j_start = j.saturating_sub(duration);
     let i_end = cmp::min(i + duration + 1, maze.height);
-    let j_end = cmp::min(j + duration + 1, maze.height);
+    let j_end = cmp::min(j + duration + 1, maze.width);
     for ii in i_start..i_end {
         for jj in j_start..j_end {
             let point = (ii, jj);
@@ -131,3 +110,30 @@ pub fn solve(input: String) -> Solution {
 
     solution
 }
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // carve a perfect maze, then keep only the unique path between its start and the open cell
+    // farthest from it so that the track is a single corridor; size is the side length
+    let maze = random::maze(rng, size);
+    let search = Search::new().start((1, 1)).record_paths().bfs(|&(i, j)| {
+        maze.neighbors(i, j)
+            .into_iter()
+            .filter(|&(ii, jj)| !maze.is_wall(ii, jj))
+    });
+    let end = maze
+        .find_all(&'.')
+        .into_iter()
+        .max_by_key(|position| search.cost(position))
+        .unwrap();
+
+    let mut track = maze.clone();
+    for (_, _, c) in track.iter_grid_mut() {
+        *c = '#';
+    }
+    for (i, j) in search.path_to(&end).unwrap() {
+        track.set(i, j, '.');
+    }
+    track.set(1, 1, 'S');
+    track.set(end.0, end.1, 'E');
+    track.to_string().trim_end().to_string()
+}
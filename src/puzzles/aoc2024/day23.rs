@@ -3,75 +3,20 @@
 */
 
 use super::Solution;
+use crate::graph;
+use crate::random::{self, Rng};
+use crate::types::Graph;
 
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::HashSet;
 
-struct NetworkMap<'a> {
-    id_to_name: HashMap<usize, &'a str>,
-    adjacency_matrix: Vec<Vec<bool>>,
-}
-
-impl<'a> NetworkMap<'a> {
-    fn computers(&self) -> impl Iterator<Item = usize> + '_ {
-        0..self.id_to_name.len()
-    }
-
-    fn computer_name(&self, id: usize) -> &str {
-        self.id_to_name[&id]
-    }
-
-    fn is_connected(&self, a: usize, b: usize) -> bool {
-        self.adjacency_matrix[a][b]
-    }
-
-    fn connections(&self, to: usize) -> impl Iterator<Item = usize> + '_ {
-        self.adjacency_matrix[to]
-            .iter()
-            .enumerate()
-            .filter(|(_, adjacent)| **adjacent)
-            .map(|(i, _)| i)
-    }
-}
-
-impl<'a> From<&'a str> for NetworkMap<'a> {
-    fn from(value: &'a str) -> Self {
-        let connections = value
-            .split('\n')
-            .map(|line| (&line[..2], &line[3..]))
-            .collect::<Vec<_>>();
-        let mut computers = connections
-            .iter()
-            .fold(HashSet::new(), |mut acc, &(a, b)| {
-                acc.insert(a);
-                acc.insert(b);
-                acc
-            })
-            .into_iter()
-            .collect::<Vec<_>>();
-        computers.sort();
-        let ids = computers
-            .into_iter()
-            .enumerate()
-            .map(|(i, c)| (c, i))
-            .collect::<HashMap<_, _>>();
-        let mut adjacency_matrix = vec![vec![false; ids.len()]; ids.len()];
-        for (a, b) in connections {
-            adjacency_matrix[ids[a]][ids[b]] = true;
-            adjacency_matrix[ids[b]][ids[a]] = true;
-        }
-        Self {
-            id_to_name: ids.into_iter().map(|(k, v)| (v, k)).collect(),
-            adjacency_matrix,
-        }
-    }
-}
+type NetworkMap = Graph<(), ()>;
 
 fn find_interconnected_computers_with_t(network_map: &NetworkMap) -> usize {
     let mut connections = HashSet::new();
-    for computer in network_map.computers() {
-        for connection in network_map.connections(computer) {
-            for subconnection in network_map.connections(connection) {
-                if subconnection != computer && network_map.is_connected(computer, subconnection) {
+    for computer in network_map.ids() {
+        for connection in network_map.neighbors(computer) {
+            for subconnection in network_map.neighbors(connection) {
+                if subconnection != computer && network_map.has_edge(computer, subconnection) {
                     let mut set = vec![computer, connection, subconnection];
                     set.sort();
                     connections.insert(set);
@@ -81,56 +26,14 @@ fn find_interconnected_computers_with_t(network_map: &NetworkMap) -> usize {
     }
     connections
         .into_iter()
-        .filter(|x| {
-            x.iter()
-                .any(|c| network_map.computer_name(*c).starts_with('t'))
-        })
+        .filter(|x| x.iter().any(|c| network_map.label(*c).starts_with('t')))
         .count()
 }
 
-fn bron_kerbosch(
-    network_map: &NetworkMap,
-    r: BTreeSet<usize>,
-    mut p: BTreeSet<usize>,
-    mut x: BTreeSet<usize>,
-    cliques: &mut Vec<Vec<usize>>,
-) {
-    if p.is_empty() && x.is_empty() {
-        cliques.push(r.into_iter().collect());
-        return;
-    }
-    for v in p.iter().copied().collect::<Vec<_>>() {
-        let mut next_r = r.clone();
-        next_r.insert(v);
-        let next_p = p
-            .iter()
-            .copied()
-            .filter(|pp| network_map.is_connected(v, *pp))
-            .collect();
-        let next_x = x
-            .iter()
-            .copied()
-            .filter(|xx| network_map.is_connected(v, *xx))
-            .collect();
-        bron_kerbosch(network_map, next_r, next_p, next_x, cliques);
-
-        p.remove(&v);
-        x.insert(v);
-    }
-}
-
 fn largest_network(network_map: &NetworkMap) -> String {
-    // implements the Bron-Kerbosch algorithm
-    let r = BTreeSet::new();
-    let p = network_map.computers().collect();
-    let x = BTreeSet::new();
-    let mut networks = Vec::new();
-    bron_kerbosch(network_map, r, p, x, &mut networks);
-
-    let largest = networks.into_iter().max_by_key(|n| n.len()).unwrap();
-    let mut network = largest
+    let mut network = graph::maximum_clique(network_map)
         .into_iter()
-        .map(|c| network_map.computer_name(c))
+        .map(|c| network_map.label(c))
         .collect::<Vec<_>>();
     network.sort();
     network.join(",")
@@ -139,7 +42,7 @@ fn largest_network(network_map: &NetworkMap) -> String {
 pub fn solve(input: String) -> Solution {
     let mut solution = Solution::new();
     // The network map provides a list of every connection between two computers.
-    let network_map = NetworkMap::from(input.as_str());
+    let network_map = NetworkMap::parse_edges(&input, "-").unwrap();
 
     // Part A: Find all the sets of three inter-connected computers. How many contain at least one
     // computer with a name that starts with t?
@@ -155,3 +58,14 @@ pub fn solve(input: String) -> Solution {
 
     solution
 }
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // size is the number of computers, each of which has on average 13 connections
+    let n = size.clamp(2, 26 * 26);
+    let names = rng.unique_names(n, 2, &[]);
+    random::graph(rng, n, n * 13 / 2)
+        .into_iter()
+        .map(|(a, b)| format!("{}-{}", names[a], names[b]))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -3,127 +3,59 @@
 */
 
 use super::Solution;
-use crate::types::Grid;
+use crate::types::{Grid, SubGrid};
 
 use log::debug;
 
-fn xmas_count_at(grid: &Grid<char>, i: usize, j: usize) -> usize {
-    // pre-condition: the tile at i,j is 'X'
-    let mut count = 0;
-    // left
-    if j >= 3
-        && *grid.get(i, j - 1) == 'M'
-        && *grid.get(i, j - 2) == 'A'
-        && *grid.get(i, j - 3) == 'S'
-    {
-        debug!("XMAS found left at {},{}", i, j);
-        count += 1;
-    }
-    // diagonal up-left
-    if i >= 3
-        && j >= 3
-        && *grid.get(i - 1, j - 1) == 'M'
-        && *grid.get(i - 2, j - 2) == 'A'
-        && *grid.get(i - 3, j - 3) == 'S'
-    {
-        debug!("XMAS found up-left at {},{}", i, j);
-        count += 1;
-    }
-    // up
-    if i >= 3
-        && *grid.get(i - 1, j) == 'M'
-        && *grid.get(i - 2, j) == 'A'
-        && *grid.get(i - 3, j) == 'S'
-    {
-        debug!("XMAS found up at {},{}", i, j);
-        count += 1;
-    }
-    // diagonal up-right
-    if i >= 3
-        && j < grid.width - 3
-        && *grid.get(i - 1, j + 1) == 'M'
-        && *grid.get(i - 2, j + 2) == 'A'
-        && *grid.get(i - 3, j + 3) == 'S'
-    {
-        debug!("XMAS found up-right at {},{}", i, j);
-        count += 1;
-    }
-    // right
-    if j < grid.width - 3
-        && *grid.get(i, j + 1) == 'M'
-        && *grid.get(i, j + 2) == 'A'
-        && *grid.get(i, j + 3) == 'S'
-    {
-        debug!("XMAS found right at {},{}", i, j);
-        count += 1;
-    }
-    // diagonal down-right
-    if i < grid.height - 3
-        && j < grid.width - 3
-        && *grid.get(i + 1, j + 1) == 'M'
-        && *grid.get(i + 2, j + 2) == 'A'
-        && *grid.get(i + 3, j + 3) == 'S'
-    {
-        debug!("XMAS found down-right at {},{}", i, j);
-        count += 1;
-    }
-    // down
-    if i < grid.height - 3
-        && *grid.get(i + 1, j) == 'M'
-        && *grid.get(i + 2, j) == 'A'
-        && *grid.get(i + 3, j) == 'S'
-    {
-        debug!("XMAS found down at {},{}", i, j);
-        count += 1;
-    }
-    // diagonal down-left
-    if i < grid.height - 3
-        && j >= 3
-        && *grid.get(i + 1, j - 1) == 'M'
-        && *grid.get(i + 2, j - 2) == 'A'
-        && *grid.get(i + 3, j - 3) == 'S'
-    {
-        debug!("XMAS found down-left at {},{}", i, j);
-        count += 1;
-    }
-    count
+fn xmas_count_in_line<'a, I>(line: I) -> usize
+where
+    I: Iterator<Item = &'a char>,
+{
+    // slide a window of the last four letters along the line, where searching for the word
+    // backwards covers the opposite direction along the same line
+    let mut window = [' '; 4];
+    line.filter(|&&c| {
+        window = [window[1], window[2], window[3], c];
+        matches!(window, ['X', 'M', 'A', 'S'] | ['S', 'A', 'M', 'X'])
+    })
+    .count()
 }
 
 fn xmas_count(grid: &Grid<char>) -> usize {
-    let mut count = 0;
-    for i in 0..grid.height {
-        for j in 0..grid.width {
-            if *grid.get(i, j) == 'X' {
-                count += xmas_count_at(grid, i, j);
-            }
-        }
-    }
-    count
+    let rows = (0..grid.height)
+        .map(|i| xmas_count_in_line(grid.iter_row(i)))
+        .sum::<usize>();
+    let cols = (0..grid.width)
+        .map(|j| xmas_count_in_line(grid.iter_col(j)))
+        .sum::<usize>();
+    let diagonals = grid
+        .diagonals()
+        .map(|line| xmas_count_in_line(line.map(|position| &grid[position])))
+        .sum::<usize>();
+    let antidiagonals = grid
+        .antidiagonals()
+        .map(|line| xmas_count_in_line(line.map(|position| &grid[position])))
+        .sum::<usize>();
+    debug!(
+        "XMAS found in rows: {}, cols: {}, diagonals: {}, antidiagonals: {}",
+        rows, cols, diagonals, antidiagonals
+    );
+    rows + cols + diagonals + antidiagonals
 }
 
-fn is_x_mas_at(grid: &Grid<char>, i: usize, j: usize) -> bool {
-    // pre-condition: the tile at i,j is 'A' and 0 < i < height and 0 < j < width
-    let mut mas_count = 0;
-    if *grid.get(i - 1, j - 1) == 'M' && *grid.get(i + 1, j + 1) == 'S' {
-        mas_count += 1;
-    }
-    if *grid.get(i - 1, j + 1) == 'M' && *grid.get(i + 1, j - 1) == 'S' {
-        mas_count += 1;
-    }
-    if *grid.get(i + 1, j + 1) == 'M' && *grid.get(i - 1, j - 1) == 'S' {
-        mas_count += 1;
-    }
-    if *grid.get(i + 1, j - 1) == 'M' && *grid.get(i - 1, j + 1) == 'S' {
-        mas_count += 1;
-    }
-    mas_count == 2
+fn is_x_mas(window: SubGrid<char>) -> bool {
+    // both diagonals of the 3x3 window must spell MAS, in either direction
+    let is_mas =
+        |a: &char, b: &char, c: &char| matches!((a, b, c), ('M', 'A', 'S') | ('S', 'A', 'M'));
+    is_mas(window.get(0, 0), window.get(1, 1), window.get(2, 2))
+        && is_mas(window.get(0, 2), window.get(1, 1), window.get(2, 0))
 }
 
 fn x_mas_count(grid: &Grid<char>) -> usize {
     let mut count = 0;
-    for i in 1..(grid.height - 1) {
-        for j in 1..(grid.width - 1) {
-            if *grid.get(i, j) == 'A' && is_x_mas_at(grid, i, j) {
+    for i in 0..(grid.height - 2) {
+        for j in 0..(grid.width - 2) {
+            if *grid.get(i + 1, j + 1) == 'A' && is_x_mas(grid.sub_grid(i, j, 3, 3)) {
                 count += 1;
             }
         }
@@ -3,6 +3,8 @@
 */
 
 use super::Solution;
+use crate::graph;
+use crate::types::Graph;
 use crate::utils;
 
 use std::collections::{HashMap, HashSet};
@@ -29,26 +31,24 @@ impl Update {
         true
     }
 
-    fn fix_order_rec(update: &mut Vec<u8>, rules: &Rules) {
-        for i in 1..update.len() {
-            if let Some(set) = rules.0.get(&update[i]) {
-                for j in 0..i {
-                    if set.contains(&update[j]) {
-                        // j is before i but i should be before j, insert i before j and recurse
-                        let n = update.remove(i);
-                        update.insert(j, n);
-                        Self::fix_order_rec(update, rules);
-                        return;
+    fn fix_order(&self, rules: &Rules) -> Self {
+        // the rules between the pages of a single update never form a cycle, so sorting them
+        // topologically gives the correct order; page k of the update is node k of the graph
+        let mut pages = Graph::<(), ()>::new();
+        for page in self.0.iter() {
+            pages.intern(&page.to_string());
+        }
+        for (i, page) in self.0.iter().enumerate() {
+            if let Some(set) = rules.0.get(page) {
+                for (j, later) in self.0.iter().enumerate() {
+                    if set.contains(later) {
+                        pages.add_edge(i, j, ());
                     }
                 }
             }
         }
-    }
-
-    fn fix_order(&self, rules: &Rules) -> Self {
-        let mut update = self.0.clone();
-        Self::fix_order_rec(&mut update, rules);
-        Self(update)
+        let order = graph::topological_sort(&pages).unwrap();
+        Self(order.into_iter().map(|k| self.0[k]).collect())
     }
 }
 
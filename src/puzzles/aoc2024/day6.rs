@@ -3,17 +3,17 @@
 */
 
 use super::Solution;
-use crate::types::{Direction, Grid};
+use crate::types::{Grid, Octile};
 
 use std::collections::HashSet;
 
 fn positions_visited(map: &Grid<char>) -> HashSet<(usize, usize)> {
     let mut position = map.find(&'^').unwrap();
-    let mut direction = Direction::North;
+    let mut direction = Octile::North;
 
     let mut positions = HashSet::new();
     while let Some(new_position) = map.neighbor(position.0, position.1, direction) {
-        if *map.get(new_position.0, new_position.1) == '#' {
+        if map[new_position] == '#' {
             direction = direction.turn_90_clockwise();
         } else {
             position = new_position;
@@ -29,14 +29,14 @@ fn obstruction_creates_loop(
     obstruction: (usize, usize),
 ) -> bool {
     let mut map = map.clone();
-    map.set(obstruction.0, obstruction.1, '#');
+    map[obstruction] = '#';
 
     let mut position = start;
-    let mut direction = Direction::North;
+    let mut direction = Octile::North;
     let mut obstructions_hit = HashSet::new();
 
     while let Some(new_position) = map.neighbor(position.0, position.1, direction) {
-        if *map.get(new_position.0, new_position.1) == '#' {
+        if map[new_position] == '#' {
             // barrier hit, check if it has been hit in this direction before
             if obstructions_hit.contains(&(new_position, direction)) {
                 return true;
@@ -4,6 +4,8 @@
 
 use super::Solution;
 use crate::itertools::*;
+use crate::random::Rng;
+use crate::utils;
 
 use std::cmp;
 use std::collections::HashMap;
@@ -14,6 +16,7 @@ type Lights = u16;
 
 struct Machine {
     indicator_lights: Lights,
+    buttons: Vec<Button>,
     button_combinations: HashMap<Lights, Vec<Vec<Button>>>,
     joltage_requirements: JoltageList,
 }
@@ -91,6 +94,153 @@ impl Machine {
         }
         solution
     }
+
+    fn configure_lights_reference(&self) -> usize {
+        // try every subset of buttons, since pressing a button twice cancels itself out
+        (0..(1u32 << self.buttons.len()))
+            .filter(|mask| {
+                let mut lights = 0;
+                for (b, button) in self.buttons.iter().enumerate() {
+                    if mask & (1 << b) != 0 {
+                        for i in button.iter() {
+                            lights ^= 1 << i;
+                        }
+                    }
+                }
+                lights == self.indicator_lights
+            })
+            .map(|mask| mask.count_ones() as usize)
+            .min()
+            .unwrap()
+    }
+
+    fn configure_joltages_reference(&self) -> Option<usize> {
+        // The button presses are a solution to the linear system Ax = b where each column of A is
+        // a button and b is the joltage requirements. A plain search over press counts is too slow
+        // for the larger machines, so reduce the system with fraction-free Gaussian elimination
+        // and then try every value of the free variables. This is trusted because:
+        // - each row operation scales a row by a nonzero pivot and subtracts another, or divides
+        //   a row by the gcd of its entries, all in exact integer arithmetic, so the reduced
+        //   system has exactly the same solutions as the original
+        // - every press count of every free button from zero up to its cap is tried, and a
+        //   button can never be pressed more often than its smallest counter allows
+        // - the presses for the pivot buttons follow from the free ones, and only whole,
+        //   non-negative counts are accepted
+        // - each accepted set of presses is checked against the joltage requirements directly
+        let n_counters = self.joltage_requirements.len();
+        let n_buttons = self.buttons.len();
+        let mut rows = (0..n_counters)
+            .map(|i| {
+                let mut row = self
+                    .buttons
+                    .iter()
+                    .map(|button| button.contains(&i) as i64)
+                    .collect::<Vec<_>>();
+                row.push(self.joltage_requirements[i] as i64);
+                row
+            })
+            .collect::<Vec<_>>();
+
+        let mut pivots = Vec::new();
+        for col in 0..n_buttons {
+            let r = pivots.len();
+            let Some(p) = (r..n_counters).find(|&i| rows[i][col] != 0) else {
+                continue;
+            };
+            rows.swap(r, p);
+            for i in 0..n_counters {
+                if i == r || rows[i][col] == 0 {
+                    continue;
+                }
+                let (a, b) = (rows[r][col], rows[i][col]);
+                let pivot_row = rows[r].clone();
+                for (x, p) in rows[i].iter_mut().zip(pivot_row.iter()) {
+                    *x = *x * a - p * b;
+                }
+                let g = rows[i]
+                    .iter()
+                    .fold(0, |g, x| utils::gcd(g, x.unsigned_abs()));
+                if g > 1 {
+                    rows[i].iter_mut().for_each(|x| *x /= g as i64);
+                }
+            }
+            pivots.push(col);
+        }
+        if rows[pivots.len()..].iter().any(|row| row[n_buttons] != 0) {
+            return None;
+        }
+
+        // a button can be pressed at most as many times as its smallest counter allows
+        let free = (0..n_buttons)
+            .filter(|col| !pivots.contains(col))
+            .collect::<Vec<_>>();
+        let bounds = free
+            .iter()
+            .map(|&col| {
+                // a button wired to no counters changes nothing, so a fewest-press solution never
+                // presses it
+                self.buttons[col]
+                    .iter()
+                    .map(|&i| self.joltage_requirements[i] as i64)
+                    .min()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        let mut solution = None;
+        let mut presses = vec![0; free.len()];
+        let mut counts = vec![0; n_buttons];
+        loop {
+            let mut total = presses.iter().sum::<i64>();
+            let mut valid = true;
+            for (&col, &x) in free.iter().zip(presses.iter()) {
+                counts[col] = x;
+            }
+            for (r, &col) in pivots.iter().enumerate() {
+                let row = &rows[r];
+                let rhs = row[n_buttons]
+                    - free
+                        .iter()
+                        .zip(presses.iter())
+                        .map(|(&f, &x)| row[f] * x)
+                        .sum::<i64>();
+                if rhs % row[col] != 0 || rhs / row[col] < 0 {
+                    valid = false;
+                    break;
+                }
+                total += rhs / row[col];
+                counts[col] = rhs / row[col];
+            }
+            if valid {
+                assert!(
+                    self.meets_requirements(&counts),
+                    "configure_joltages_reference: presses do not meet the requirements"
+                );
+                solution = Some(solution.map_or(total, |s: i64| cmp::min(s, total)));
+            }
+
+            // advance to the next assignment of the free variables
+            let Some(f) = (0..free.len()).find(|&f| presses[f] < bounds[f]) else {
+                break;
+            };
+            presses[f] += 1;
+            presses[..f].iter_mut().for_each(|x| *x = 0);
+        }
+        solution.map(|s| s as usize)
+    }
+
+    fn meets_requirements(&self, counts: &[i64]) -> bool {
+        let mut joltages = vec![0; self.joltage_requirements.len()];
+        for (button, &count) in self.buttons.iter().zip(counts) {
+            for &i in button {
+                joltages[i] += count;
+            }
+        }
+        joltages
+            .iter()
+            .zip(self.joltage_requirements.iter())
+            .all(|(&joltage, &required)| joltage == required as i64)
+    }
 }
 
 impl From<&str> for Machine {
@@ -131,6 +281,7 @@ impl From<&str> for Machine {
 
         Self {
             indicator_lights,
+            buttons: button_wiring,
             button_combinations,
             joltage_requirements,
         }
@@ -164,3 +315,77 @@ pub fn solve(input: String) -> Solution {
 
     solution
 }
+
+pub fn solve_reference(input: String) -> Solution {
+    let mut solution = Solution::new();
+    let machines = input.split('\n').map(Machine::from).collect::<Vec<_>>();
+
+    // Part A: exhaustively search every subset of buttons
+    let button_presses = machines
+        .iter()
+        .map(|machine| machine.configure_lights_reference())
+        .sum::<usize>();
+    solution.set_part_a(button_presses);
+
+    // Part B: solve the linear system for the button presses directly
+    let joltage_presses = machines
+        .iter()
+        .filter_map(|machine| machine.configure_joltages_reference())
+        .sum::<usize>();
+    solution.set_part_b(joltage_presses);
+
+    solution
+}
+
+fn generate_machine(rng: &mut Rng) -> String {
+    // machines have a similar number of buttons and lights
+    let n_lights = rng.range(3, 10) as usize;
+    let n_buttons = rng.range(n_lights as u64 - 2, n_lights as u64 + 3) as usize;
+    let buttons = (0..n_buttons)
+        .map(|_| {
+            let mut lights = (0..n_lights).collect::<Vec<_>>();
+            rng.shuffle(&mut lights);
+            lights.truncate(rng.range(1, n_lights as u64) as usize);
+            lights.sort();
+            lights
+        })
+        .collect::<Vec<_>>();
+
+    // derive the lights and joltages from random button presses so that both are reachable
+    let mut indicator_lights = vec!['.'; n_lights];
+    let mut joltages = vec![0; n_lights];
+    for button in buttons.iter() {
+        let presses = rng.below(20);
+        for &i in button.iter() {
+            joltages[i] += presses;
+        }
+        if rng.chance(0.5) {
+            for &i in button.iter() {
+                indicator_lights[i] = if indicator_lights[i] == '#' { '.' } else { '#' };
+            }
+        }
+    }
+
+    let wiring = buttons
+        .iter()
+        .map(|button| {
+            let lights = button.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+            format!("({})", lights.join(","))
+        })
+        .collect::<Vec<_>>();
+    let joltages = joltages.iter().map(|j| j.to_string()).collect::<Vec<_>>();
+    format!(
+        "[{}] {} {{{}}}",
+        indicator_lights.into_iter().collect::<String>(),
+        wiring.join(" "),
+        joltages.join(",")
+    )
+}
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // size is the number of machines
+    (0..size.max(1))
+        .map(|_| generate_machine(rng))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
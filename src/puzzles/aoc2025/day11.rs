@@ -3,64 +3,20 @@
 */
 
 use super::Solution;
-use crate::utils;
+use crate::graph;
+use crate::random::{self, Rng};
+use crate::types::Graph;
 
-use std::collections::HashMap;
+use std::cmp;
 
-const INPUT: u16 = u16::MIN;
-const OUTPUT: u16 = u16::MAX;
+type DeviceMap = Graph<(), ()>;
 
-fn char_id(c: char) -> u16 {
-    ((c as u8) - b'a') as u16
-}
-
-fn device_id(device: &str) -> u16 {
-    if device == "you" {
-        INPUT
-    } else if device == "out" {
-        OUTPUT
-    } else {
-        let chars = device.chars().collect::<Vec<_>>();
-        assert_eq!(chars.len(), 3);
-        (1 << 15) | (char_id(chars[0]) << 10) | (char_id(chars[1]) << 5) | char_id(chars[2])
-    }
-}
-
-fn build_connection_map(input: String) -> HashMap<u16, Vec<u16>> {
-    let mut map = HashMap::new();
-    for line in input.split('\n') {
-        let (input, outputs) = utils::split(line, ": ").unwrap();
-        let output_ids = outputs.split(' ').map(device_id).collect();
-        map.insert(device_id(input), output_ids);
-    }
-    map
-}
-
-fn find_paths_rec(
-    map: &HashMap<u16, Vec<u16>>,
-    current: u16,
-    target: u16,
-    memo: &mut HashMap<u16, usize>,
-) -> usize {
-    if current == target {
-        1
-    } else if memo.contains_key(&current) {
-        *memo.get(&current).unwrap()
-    } else {
-        let paths = map
-            .get(&current)
-            .unwrap_or(&Vec::new())
-            .iter()
-            .map(|device| find_paths_rec(map, *device, target, memo))
-            .sum();
-        memo.insert(current, paths);
-        paths
-    }
-}
-
-fn find_paths(map: &HashMap<u16, Vec<u16>>, from: u16, to: u16) -> usize {
-    let mut memo = HashMap::new();
-    find_paths_rec(map, from, to, &mut memo)
+fn find_paths(map: &DeviceMap, from: &str, to: &str) -> usize {
+    // there are no paths to or from devices which are not in the list
+    let (Some(from), Some(to)) = (map.id(from), map.id(to)) else {
+        return 0;
+    };
+    graph::count_paths(map, from, to).unwrap()
 }
 
 pub fn solve(input: String) -> Solution {
@@ -68,23 +24,53 @@ pub fn solve(input: String) -> Solution {
     // You glance around the room and see a tangle of cables and devices running from the server
     // rack to the reactor. The elf rushes off, returning a moment later with a list of the devices
     // and their outputs.
-    let connections = build_connection_map(input);
+    let connections = DeviceMap::parse_adjacency(&input).unwrap();
 
     // Part A: How many different paths lead from you to out?
-    let paths = find_paths(&connections, INPUT, OUTPUT);
+    let paths = find_paths(&connections, "you", "out");
     solution.set_part_a(paths);
 
     // Part B: Find all of the paths that lead from svr to out. How many of those paths visit both
     // dac and fft?
-    let svr_to_dac = find_paths(&connections, device_id("svr"), device_id("dac"));
-    let svr_to_fft = find_paths(&connections, device_id("svr"), device_id("fft"));
-    let dac_to_fft = find_paths(&connections, device_id("dac"), device_id("fft"));
-    let fft_to_dac = find_paths(&connections, device_id("fft"), device_id("dac"));
-    let dac_to_out = find_paths(&connections, device_id("dac"), OUTPUT);
-    let fft_to_out = find_paths(&connections, device_id("fft"), OUTPUT);
+    let svr_to_dac = find_paths(&connections, "svr", "dac");
+    let svr_to_fft = find_paths(&connections, "svr", "fft");
+    let dac_to_fft = find_paths(&connections, "dac", "fft");
+    let fft_to_dac = find_paths(&connections, "fft", "dac");
+    let dac_to_out = find_paths(&connections, "dac", "out");
+    let fft_to_out = find_paths(&connections, "fft", "out");
     let route_a = svr_to_dac * dac_to_fft * fft_to_out;
     let route_b = svr_to_fft * fft_to_dac * dac_to_out;
     solution.set_part_b(route_a + route_b);
 
     solution
 }
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // size is the number of devices; connections only lead to nearby devices later in the list so
+    // that the network has no loops and a bounded number of paths
+    const SPECIAL: [&str; 5] = ["svr", "you", "dac", "fft", "out"];
+    let n = size.max(SPECIAL.len());
+    let mut names = rng.unique_names(n - SPECIAL.len(), 3, &SPECIAL);
+    // keep the special devices in their route order, spread through the list
+    for (k, special) in SPECIAL.iter().enumerate() {
+        let index = if k == SPECIAL.len() - 1 {
+            names.len()
+        } else {
+            (k * names.len()) / (SPECIAL.len() - 1)
+        };
+        names.insert(index, special.to_string());
+    }
+    random::dag(rng, n, 3, cmp::max(n / 8, 2))
+        .into_iter()
+        .enumerate()
+        .filter(|(_, outputs)| !outputs.is_empty())
+        .map(|(i, outputs)| {
+            let outputs = outputs
+                .iter()
+                .map(|&o| names[o].as_str())
+                .collect::<Vec<_>>();
+            format!("{}: {}", names[i], outputs.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
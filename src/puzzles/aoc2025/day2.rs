@@ -4,12 +4,13 @@
 
 use super::Solution;
 use crate::itertools::*;
-use crate::types::RangeInclusive;
 use crate::utils;
 
+use std::ops::RangeInclusive;
+
 fn parse_range(value: &str) -> RangeInclusive<u64> {
     let (start, end) = utils::split(value, "-").unwrap();
-    RangeInclusive::new(start.parse().unwrap(), end.parse().unwrap())
+    start.parse().unwrap()..=end.parse().unwrap()
 }
 
 fn extract_digits(number: u64, count: u32, offset: u32) -> u64 {
@@ -55,7 +56,7 @@ pub fn solve(input: String) -> Solution {
     // sequence of digits repeated twice. What do you get if you add up all of the invalid IDs?
     let mut sum = 0;
     for range in product_ids.iter() {
-        for id in range.start..=range.end {
+        for id in range.clone() {
             if !is_valid_id(id) {
                 sum += id;
             }
@@ -67,7 +68,7 @@ pub fn solve(input: String) -> Solution {
     // least twice. What do you get if you add up all of the invalid IDs using these new rules?
     sum = 0;
     for range in product_ids.iter() {
-        for id in range.start..=range.end {
+        for id in range.clone() {
             if !is_valid_id_expanded(id) {
                 sum += id;
             }
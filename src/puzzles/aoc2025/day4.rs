@@ -3,6 +3,7 @@
 */
 
 use super::Solution;
+use crate::automaton::{GridAutomaton, Neighborhood};
 use crate::types::Grid;
 
 #[derive(Clone, PartialEq)]
@@ -11,62 +12,45 @@ enum State {
     Paper,
 }
 
-impl From<char> for State {
-    fn from(value: char) -> Self {
-        match value {
-            '.' => Self::Empty,
-            '@' => Self::Paper,
-            _ => unreachable!(),
-        }
-    }
-}
+impl TryFrom<char> for State {
+    type Error = char;
 
-fn accessible_rolls(grid: &Grid<State>) -> Vec<(usize, usize)> {
-    let mut rolls = Vec::new();
-    for (i, j, state) in grid.iter_grid() {
-        if state == &State::Paper {
-            let adjacent_rolls = grid
-                .neighbors_with_diagonal(i, j)
-                .into_iter()
-                .filter(|&(ii, jj)| grid.get(ii, jj) == &State::Paper)
-                .count();
-            if adjacent_rolls < 4 {
-                rolls.push((i, j));
-            }
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '.' => Ok(Self::Empty),
+            '@' => Ok(Self::Paper),
+            _ => Err(value),
         }
     }
-    rolls
-}
-
-fn remove_rolls(grid: &mut Grid<State>, rolls: &[(usize, usize)]) {
-    for &(i, j) in rolls {
-        grid.set(i, j, State::Empty);
-    }
 }
 
 pub fn solve(input: String) -> Solution {
     let mut solution = Solution::new();
     // The rolls of paper are arranged on a large grid
-    let mut grid = input
-        .split('\n')
-        .map(|line| line.chars().map(State::from))
-        .collect::<Grid<_>>();
+    let grid = input.parse::<Grid<State>>().unwrap();
+    // a roll can be accessed, and so removed, if fewer than 4 of the 8 adjacent positions have
+    // rolls in them
+    let mut automaton = GridAutomaton::new(grid, Neighborhood::Moore, |state, neighbors| {
+        let adjacent_rolls = neighbors
+            .iter()
+            .filter(|&&state| state == &State::Paper)
+            .count();
+        if state == &State::Paper && adjacent_rolls < 4 {
+            State::Empty
+        } else {
+            state.clone()
+        }
+    });
+    let rolls = automaton.count(|state| state == &State::Paper);
 
     // Part A: How many rolls of paper can be accessed by a forklift?
-    let rolls = accessible_rolls(&grid);
-    solution.set_part_a(rolls.len());
+    let accessible = automaton.step();
+    solution.set_part_a(accessible);
 
     // Part B: How many rolls of paper in total can be removed by the Elves and their forklifts?
-    let mut removed = rolls.len();
-    let mut total = removed;
-    remove_rolls(&mut grid, &rolls);
-    while removed > 0 {
-        let rolls = accessible_rolls(&grid);
-        removed = rolls.len();
-        total += removed;
-        remove_rolls(&mut grid, &rolls);
-    }
-    solution.set_part_b(total);
+    automaton.run_until_stable();
+    let removed = rolls - automaton.count(|state| state == &State::Paper);
+    solution.set_part_b(removed);
 
     solution
 }
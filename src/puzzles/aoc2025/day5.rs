@@ -3,29 +3,18 @@
 */
 
 use super::Solution;
-use crate::types::RangeInclusive;
+use crate::random::{self, Rng};
+use crate::types::IntervalSet;
 use crate::utils;
 
 struct Database {
-    fresh_ingredients: Vec<RangeInclusive<u64>>,
+    fresh_ingredients: IntervalSet<u64>,
     available_ingredients: Vec<u64>,
 }
 
 impl Database {
-    fn new(fresh_ingredients: Vec<RangeInclusive<u64>>, available_ingredients: Vec<u64>) -> Self {
-        Self {
-            fresh_ingredients: RangeInclusive::reduce(fresh_ingredients),
-            available_ingredients,
-        }
-    }
-
     fn ingredient_is_fresh(&self, ingredient: u64) -> bool {
-        for range in self.fresh_ingredients.iter() {
-            if range.contains(&ingredient) {
-                return true;
-            }
-        }
-        false
+        self.fresh_ingredients.contains(&ingredient)
     }
 }
 
@@ -37,14 +26,18 @@ impl From<String> for Database {
             .split('\n')
             .map(|line| {
                 let (start, end) = utils::split(line, "-").unwrap();
-                RangeInclusive::new(start.parse().unwrap(), end.parse().unwrap())
+                // the ranges are inclusive
+                start.parse().unwrap()..(end.parse::<u64>().unwrap() + 1)
             })
             .collect();
         let available_ingredients = available_ingredient_lines
             .split('\n')
             .map(|line| line.parse().unwrap())
             .collect();
-        Self::new(fresh_ingredients, available_ingredients)
+        Self {
+            fresh_ingredients,
+            available_ingredients,
+        }
     }
 }
 
@@ -65,12 +58,23 @@ pub fn solve(input: String) -> Solution {
 
     // Part B: Process the database file again. How many ingredient IDs are considered to be fresh
     // according to the fresh ingredient ID ranges?
-    let ingredients = database
-        .fresh_ingredients
-        .iter()
-        .map(|range| range.size() + 1)
-        .sum::<u64>();
+    let ingredients = database.fresh_ingredients.len();
     solution.set_part_b(ingredients);
 
     solution
 }
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // size is the number of fresh ingredient ranges, with 5 available ingredients for each
+    const MAX_ID: u64 = 1 << 49;
+    let ranges = random::ranges(rng, size.max(1), MAX_ID, MAX_ID / 1000)
+        .into_iter()
+        .map(|(start, end)| format!("{}-{}", start, end))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let ingredients = (0..(size.max(1) * 5))
+        .map(|_| rng.below(MAX_ID).to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("{}\n\n{}", ranges, ingredients)
+}
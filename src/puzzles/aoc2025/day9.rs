@@ -3,38 +3,19 @@
 */
 
 use super::Solution;
-use crate::types::{Line, Point, RangeInclusive};
-
-use std::cmp;
+use crate::geometry::RectilinearPolygon;
+use crate::types::Point;
 
 struct Rectangle {
     area: i64,
-    x: RangeInclusive<i64>,
-    y: RangeInclusive<i64>,
+    a: Point,
+    b: Point,
 }
 
 impl Rectangle {
     fn new(a: Point, b: Point) -> Self {
         let area = ((b.x - a.x).abs() + 1) * ((b.y - a.y).abs() + 1);
-        let x = RangeInclusive::new(cmp::min(a.x, b.x), cmp::max(a.x, b.x));
-        let y = RangeInclusive::new(cmp::min(a.y, b.y), cmp::max(a.y, b.y));
-        Self { area, x, y }
-    }
-
-    fn contains_line(&self, line: &Line) -> bool {
-        if line.is_horizontal() {
-            line.p0.y > self.y.start
-                && line.p0.y < self.y.end
-                && line.p0.x <= self.x.end
-                && line.p1.x >= self.x.start
-        } else if line.is_vertical() {
-            line.p0.x > self.x.start
-                && line.p0.x < self.x.end
-                && line.p0.y <= self.y.end
-                && line.p1.y >= self.y.start
-        } else {
-            unreachable!()
-        }
+        Self { area, a, b }
     }
 }
 
@@ -48,39 +29,13 @@ fn generate_rectangles(points: &[Point]) -> Vec<Rectangle> {
     rectangles
 }
 
-fn ordered_line(a: Point, b: Point) -> Line {
-    if a.x == b.x {
-        let p0 = Point::new(a.x, cmp::min(a.y, b.y));
-        let p1 = Point::new(a.x, cmp::max(a.y, b.y));
-        Line::new(p0, p1)
-    } else if a.y == b.y {
-        let p0 = Point::new(cmp::min(a.x, b.x), a.y);
-        let p1 = Point::new(cmp::max(a.x, b.x), a.y);
-        Line::new(p0, p1)
-    } else {
-        unreachable!()
-    }
-}
-
-fn generate_edges(points: &[Point]) -> Vec<Line> {
-    let mut edges = Vec::with_capacity(points.len() + 1);
-    for (i, a) in points[..(points.len() - 1)].iter().enumerate() {
-        edges.push(Line::new(*a, points[i + 1]));
-    }
-    edges.push(ordered_line(points[points.len() - 1], points[0]));
-    edges
-}
-
-fn largest_inner_rectangle(rectangles: &[Rectangle], edges: &[Line]) -> Option<i64> {
-    'outer: for rectangle in rectangles {
-        for edge in edges.iter() {
-            if rectangle.contains_line(edge) {
-                continue 'outer;
-            }
-        }
-        return Some(rectangle.area);
-    }
-    None
+fn largest_inner_rectangle(rectangles: &[Rectangle], red_tiles: &[Point]) -> Option<i64> {
+    // the red tiles are the corners of a polygon which contains all the green tiles
+    let polygon = RectilinearPolygon::new(red_tiles);
+    rectangles
+        .iter()
+        .find(|rectangle| polygon.contains_rectangle(rectangle.a, rectangle.b))
+        .map(|rectangle| rectangle.area)
 }
 
 pub fn solve(input: String) -> Solution {
@@ -101,8 +56,7 @@ pub fn solve(input: String) -> Solution {
 
     // Part B: Using two red tiles as opposite corners, what is the largest area of any rectangle
     // you can make using only red and green tiles?
-    let edges = generate_edges(&red_tiles);
-    let area = largest_inner_rectangle(&rectangles, &edges);
+    let area = largest_inner_rectangle(&rectangles, &red_tiles);
     solution.maybe_set_part_b(area);
 
     solution
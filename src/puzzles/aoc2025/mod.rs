@@ -3,5 +3,7 @@
 */
 
 crate::puzzle_set!(
-    day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12
+    day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12;
+    reference: day10;
+    generator: day5, day10, day11
 );
@@ -11,6 +11,9 @@ crate::puzzle_modules!(
     2025 => aoc2025
 );
 
+/// Produces a valid puzzle input of roughly the requested size
+pub type Generator = fn(&mut crate::random::Rng, usize) -> String;
+
 #[derive(Clone, Copy, Eq, Hash, PartialEq)]
 pub struct Puzzle {
     pub year: usize,
@@ -0,0 +1,216 @@
+/*
+** src/random.rs
+*/
+
+use crate::types::{Grid, Maze, Point};
+
+use std::collections::HashSet;
+
+/// Small, seedable pseudo-random number generator (SplitMix64)
+#[derive(Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in the range [0, n)
+    pub fn below(&mut self, n: u64) -> u64 {
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    /// Returns a value in the inclusive range [low, high]
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.below(high - low + 1)
+    }
+
+    /// Returns true with the given probability
+    pub fn chance(&mut self, probability: f64) -> bool {
+        let sample = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        sample < probability
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    /// Generates a lowercase name of the given length
+    pub fn name(&mut self, length: usize) -> String {
+        (0..length)
+            .map(|_| (b'a' + self.below(26) as u8) as char)
+            .collect()
+    }
+
+    /// Generates the given number of distinct lowercase names, skipping any reserved names
+    pub fn unique_names(&mut self, count: usize, length: usize, reserved: &[&str]) -> Vec<String> {
+        let mut names = HashSet::new();
+        let mut ordered = Vec::with_capacity(count);
+        while ordered.len() < count {
+            let name = self.name(length);
+            if !reserved.contains(&name.as_str()) && names.insert(name.clone()) {
+                ordered.push(name);
+            }
+        }
+        ordered
+    }
+}
+
+/// Generates a grid where each cell is filled by the given function
+pub fn grid<T, F>(rng: &mut Rng, width: usize, height: usize, mut fill: F) -> Grid<T>
+where
+    T: Clone,
+    F: FnMut(&mut Rng) -> T,
+{
+    let rows = (0..height)
+        .map(|_| (0..width).map(|_| fill(rng)).collect())
+        .collect::<Vec<Vec<T>>>();
+    Grid::from(rows)
+}
+
+/// Generates a perfect maze (exactly one path between any two open cells) by carving a random
+/// spanning tree. Open cells sit at odd coordinates, so the size is rounded up to be odd.
+pub fn maze(rng: &mut Rng, size: usize) -> Maze {
+    let size = size.max(5) | 1;
+    let mut maze = Maze::create(size, size);
+    for (_, _, c) in maze.iter_grid_mut() {
+        *c = '#';
+    }
+
+    let mut stack = vec![(1, 1)];
+    maze.set(1, 1, '.');
+    while let Some(&(i, j)) = stack.last() {
+        let mut candidates = [(0, 2), (2, 0), (0, -2), (-2, 0)]
+            .into_iter()
+            .map(|(di, dj)| (i as i64 + di, j as i64 + dj))
+            .filter(|&(ii, jj)| ii > 0 && jj > 0 && ii < size as i64 && jj < size as i64)
+            .map(|(ii, jj)| (ii as usize, jj as usize))
+            .filter(|&(ii, jj)| maze.is_wall(ii, jj))
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            stack.pop();
+            continue;
+        }
+        rng.shuffle(&mut candidates);
+        let (ii, jj) = candidates[0];
+        maze.set((i + ii) / 2, (j + jj) / 2, '.');
+        maze.set(ii, jj, '.');
+        stack.push((ii, jj));
+    }
+    maze
+}
+
+/// Generates the given number of distinct undirected edges between nodes [0, n)
+pub fn graph(rng: &mut Rng, n: usize, edges: usize) -> Vec<(usize, usize)> {
+    let edges = edges.min(n * n.saturating_sub(1) / 2);
+    let mut seen = HashSet::new();
+    let mut output = Vec::with_capacity(edges);
+    while output.len() < edges {
+        let a = rng.below(n as u64) as usize;
+        let b = rng.below(n as u64) as usize;
+        if a != b && seen.insert((a.min(b), a.max(b))) {
+            output.push((a, b));
+        }
+    }
+    output
+}
+
+/// Generates a directed acyclic graph over nodes [0, n), where each node has edges only to the
+/// nodes within the following window, which bounds the depth of the graph
+pub fn dag(rng: &mut Rng, n: usize, max_out_degree: usize, window: usize) -> Vec<Vec<usize>> {
+    let window = window.max(1);
+    (0..n)
+        .map(|i| {
+            if i + 1 >= n {
+                return Vec::new();
+            }
+            let degree = rng.range(1, max_out_degree as u64) as usize;
+            let mut targets = (0..degree)
+                .map(|_| i + 1 + rng.below(window.min(n - i - 1) as u64) as usize)
+                .collect::<Vec<_>>();
+            targets.sort();
+            targets.dedup();
+            targets
+        })
+        .collect()
+}
+
+/// Generates the given number of (possibly overlapping) inclusive ranges within [0, max_value]
+pub fn ranges(rng: &mut Rng, count: usize, max_value: u64, max_length: u64) -> Vec<(u64, u64)> {
+    (0..count)
+        .map(|_| {
+            let start = rng.below(max_value - max_length);
+            (start, start + rng.below(max_length))
+        })
+        .collect()
+}
+
+/// Generates the given number of distinct points within [0, width) x [0, height)
+pub fn points(rng: &mut Rng, count: usize, width: usize, height: usize) -> Vec<Point> {
+    let mut points = (0..height)
+        .flat_map(|y| (0..width).map(move |x| Point::new_for_grid(x, y)))
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut points);
+    points.truncate(count);
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles::{PuzzleIterator, PuzzleModules};
+
+    #[test]
+    fn splitmix64() {
+        // the first outputs of the reference implementation seeded with 1234567
+        let mut rng = Rng::new(1234567);
+        let expected = [
+            6457827717110365317,
+            3203168211198807973,
+            9817491932198370423,
+            4593380528125082431,
+            16408922859458223821,
+        ];
+        assert_eq!(expected.map(|_| rng.next_u64()), expected);
+
+        // the same seed always gives the same values, and other seeds do not
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let mut c = Rng::new(43);
+        let draws = |rng: &mut Rng| (0..16).map(|_| rng.range(3, 9)).collect::<Vec<_>>();
+        assert_eq!(draws(&mut a), draws(&mut b));
+        assert_ne!(draws(&mut b), draws(&mut c));
+        assert!(draws(&mut a).iter().all(|value| (3..=9).contains(value)));
+    }
+
+    #[test]
+    fn generated_inputs_solve() {
+        let puzzles = PuzzleIterator::all().filter_map(|puzzle| {
+            PuzzleModules::generator(puzzle.year, puzzle.day).map(|generator| (puzzle, generator))
+        });
+        for (puzzle, generator) in puzzles {
+            let input = generator(&mut Rng::new(7), 8);
+            assert_eq!(input, generator(&mut Rng::new(7), 8), "{}", puzzle);
+            let solution = PuzzleModules::dispatch(puzzle.year, puzzle.day)(input);
+            // part B may have no answer, such as when too few bytes fall to block the way out
+            assert!(
+                solution.part_a.is_some(),
+                "{}: no answer for a generated input",
+                puzzle
+            );
+        }
+    }
+}
@@ -0,0 +1,727 @@
+/*
+** src/search.rs
+*/
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Path costs, where the default value is zero
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<T> Cost for T where T: Copy + Ord + Add<Output = T> + Default {}
+
+type Goal<'a, S> = Box<dyn Fn(&S) -> bool + 'a>;
+type Heuristic<'a, S, C> = Box<dyn Fn(&S) -> C + 'a>;
+type Predecessors<S> = HashMap<S, Vec<S>>;
+
+// records a step into a state, either alongside the equally short steps found before it or
+// replacing them if the step is shorter
+fn record_step<S: Clone + Eq + Hash>(
+    predecessors: &mut Option<Predecessors<S>>,
+    state: &S,
+    from: Option<&S>,
+    shorter: bool,
+) {
+    if let Some(predecessors) = predecessors {
+        let entry = predecessors.entry(state.clone()).or_default();
+        if shorter {
+            entry.clear();
+        }
+        entry.extend(from.cloned());
+    }
+}
+
+/// Shortest-path search over any hashable state. Dijkstra's algorithm is used by default, A* if
+/// a heuristic is given, and breadth-first search if every step costs the same.
+pub struct Search<'a, S, C> {
+    starts: Vec<(S, C)>,
+    goal: Option<Goal<'a, S>>,
+    heuristic: Option<Heuristic<'a, S, C>>,
+    record_paths: bool,
+}
+
+impl<'a, S, C> Search<'a, S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+{
+    pub fn new() -> Self {
+        Self {
+            starts: Vec::new(),
+            goal: None,
+            heuristic: None,
+            record_paths: false,
+        }
+    }
+
+    pub fn start(self, state: S) -> Self {
+        self.start_with_cost(state, C::default())
+    }
+
+    /// Adds a starting state which has already accrued the given cost
+    pub fn start_with_cost(mut self, state: S, cost: C) -> Self {
+        self.starts.push((state, cost));
+        self
+    }
+
+    pub fn starts<I>(self, states: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+    {
+        states
+            .into_iter()
+            .fold(self, |search, state| search.start(state))
+    }
+
+    /// Stops the search once a state matching the predicate has been reached
+    pub fn goal<G>(mut self, goal: G) -> Self
+    where
+        G: Fn(&S) -> bool + 'a,
+    {
+        self.goal = Some(Box::new(goal));
+        self
+    }
+
+    /// Estimates the remaining cost from a state to the goal. Settled states are never reopened,
+    /// so the estimate must be consistent: it may drop by at most the cost of each step, and is
+    /// zero at the goal, which also means it never overestimates
+    #[allow(unused)]
+    pub fn heuristic<H>(mut self, heuristic: H) -> Self
+    where
+        H: Fn(&S) -> C + 'a,
+    {
+        self.heuristic = Some(Box::new(heuristic));
+        self
+    }
+
+    /// Records every step which lies on a shortest path, so that paths can be recovered from the
+    /// result
+    pub fn record_paths(mut self) -> Self {
+        self.record_paths = true;
+        self
+    }
+
+    fn predecessors(&self) -> Option<Predecessors<S>> {
+        self.record_paths.then(HashMap::new)
+    }
+
+    /// Visits states in order of increasing cost, given a function which returns the states
+    /// reachable from a state and the cost of each step
+    pub fn iter<F, I>(self, successors: F) -> Visit<'a, S, C, F>
+    where
+        F: FnMut(&S) -> I,
+        I: IntoIterator<Item = (S, C)>,
+    {
+        let mut visit = Visit {
+            predecessors: self.predecessors(),
+            successors,
+            goal: self.goal,
+            heuristic: self.heuristic,
+            best: HashMap::new(),
+            settled: HashSet::new(),
+            heap: BinaryHeap::new(),
+            done: false,
+        };
+        for (state, cost) in self.starts {
+            visit.push(state, cost, None);
+        }
+        visit
+    }
+
+    pub fn run<F, I>(self, successors: F) -> SearchResult<S, C>
+    where
+        F: FnMut(&S) -> I,
+        I: IntoIterator<Item = (S, C)>,
+    {
+        let mut visit = self.iter(successors);
+        let result = SearchResult::from_visit(&mut visit);
+        result
+            .with_goal(visit.done)
+            .with_predecessors(visit.predecessors)
+    }
+}
+
+impl<'a, S> Search<'a, S, usize>
+where
+    S: Clone + Eq + Hash,
+{
+    /// Visits states in breadth-first order, where every step costs 1
+    pub fn iter_bfs<F, I>(self, successors: F) -> BfsVisit<'a, S, F>
+    where
+        F: FnMut(&S) -> I,
+        I: IntoIterator<Item = S>,
+    {
+        let mut predecessors = self.predecessors();
+        let mut costs = HashMap::new();
+        let mut queue = VecDeque::new();
+        for (state, cost) in self.starts {
+            if !costs.contains_key(&state) {
+                record_step(&mut predecessors, &state, None, true);
+                costs.insert(state.clone(), cost);
+                queue.push_back((state, cost));
+            }
+        }
+        BfsVisit {
+            successors,
+            goal: self.goal,
+            costs,
+            queue,
+            predecessors,
+            done: false,
+        }
+    }
+
+    pub fn bfs<F, I>(self, successors: F) -> SearchResult<S, usize>
+    where
+        F: FnMut(&S) -> I,
+        I: IntoIterator<Item = S>,
+    {
+        let mut visit = self.iter_bfs(successors);
+        let result = SearchResult::from_visit(&mut visit);
+        result
+            .with_goal(visit.done)
+            .with_predecessors(visit.predecessors)
+    }
+}
+
+impl<'a, C> Search<'a, usize, C>
+where
+    C: Cost,
+{
+    /// Runs the search over states numbered below len, keeping costs in a vector rather than
+    /// hashing every state, and returns the cost of each state which was settled. Paths are not
+    /// recorded.
+    pub fn run_dense<F, I>(self, len: usize, mut successors: F) -> Vec<Option<C>>
+    where
+        F: FnMut(usize) -> I,
+        I: IntoIterator<Item = (usize, C)>,
+    {
+        let priority = |state: usize, cost: C| match &self.heuristic {
+            Some(heuristic) => cost + heuristic(&state),
+            None => cost,
+        };
+        let mut best = vec![None; len];
+        let mut settled = vec![false; len];
+        let mut heap = BinaryHeap::new();
+        for (state, cost) in self.starts.iter().copied() {
+            if best[state].is_none_or(|best| cost < best) {
+                best[state] = Some(cost);
+                heap.push(Entry {
+                    priority: priority(state, cost),
+                    cost,
+                    state,
+                });
+            }
+        }
+        while let Some(Entry { cost, state, .. }) = heap.pop() {
+            if settled[state] {
+                continue;
+            }
+            settled[state] = true;
+            if self.goal.as_ref().is_some_and(|goal| goal(&state)) {
+                break;
+            }
+            for (next, step) in successors(state) {
+                let next_cost = cost + step;
+                // a settled state already has a cost no greater than any step into it
+                if best[next].is_none_or(|best| next_cost < best) {
+                    best[next] = Some(next_cost);
+                    heap.push(Entry {
+                        priority: priority(next, next_cost),
+                        cost: next_cost,
+                        state: next,
+                    });
+                }
+            }
+        }
+        // states which were reached but not settled may not have their final cost
+        for (cost, settled) in best.iter_mut().zip(settled) {
+            if !settled {
+                *cost = None;
+            }
+        }
+        best
+    }
+}
+
+impl<'a> Search<'a, usize, usize> {
+    /// Breadth-first search over states numbered below len, keeping costs in a vector rather
+    /// than hashing every state, and returns the cost of each state which was reached. Paths are
+    /// not recorded.
+    pub fn bfs_dense<F, I>(self, len: usize, mut successors: F) -> Vec<Option<usize>>
+    where
+        F: FnMut(usize) -> I,
+        I: IntoIterator<Item = usize>,
+    {
+        let mut costs = vec![None; len];
+        let mut queue = VecDeque::new();
+        for &(state, cost) in self.starts.iter() {
+            if costs[state].is_none() {
+                costs[state] = Some(cost);
+                queue.push_back((state, cost));
+            }
+        }
+        while let Some((state, cost)) = queue.pop_front() {
+            if self.goal.as_ref().is_some_and(|goal| goal(&state)) {
+                break;
+            }
+            for next in successors(state) {
+                if costs[next].is_none() {
+                    costs[next] = Some(cost + 1);
+                    queue.push_back((next, cost + 1));
+                }
+            }
+        }
+        costs
+    }
+}
+
+struct Entry<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> Ord for Entry<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // reverse the ordering on priority for the max-heap
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl<S, C: Ord> PartialOrd for Entry<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> PartialEq for Entry<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S, C: Ord> Eq for Entry<S, C> {}
+
+/// Iterator over the states of a search in the order they are settled, with their final cost
+pub struct Visit<'a, S, C, F> {
+    successors: F,
+    goal: Option<Goal<'a, S>>,
+    heuristic: Option<Heuristic<'a, S, C>>,
+    // lowest cost found so far for each state
+    best: HashMap<S, C>,
+    settled: HashSet<S>,
+    heap: BinaryHeap<Entry<S, C>>,
+    predecessors: Option<Predecessors<S>>,
+    done: bool,
+}
+
+impl<S, C, F> Visit<'_, S, C, F>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+{
+    fn push(&mut self, state: S, cost: C, from: Option<&S>) {
+        match self.best.get(&state) {
+            Some(&best) if best < cost => return,
+            Some(&best) if best == cost => {
+                record_step(&mut self.predecessors, &state, from, false);
+                return;
+            }
+            _ => record_step(&mut self.predecessors, &state, from, true),
+        }
+        let priority = match &self.heuristic {
+            Some(heuristic) => cost + heuristic(&state),
+            None => cost,
+        };
+        self.best.insert(state.clone(), cost);
+        self.heap.push(Entry {
+            priority,
+            cost,
+            state,
+        });
+    }
+}
+
+impl<S, C, F, I> Iterator for Visit<'_, S, C, F>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    type Item = (S, C);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        while let Some(Entry { cost, state, .. }) = self.heap.pop() {
+            // skip states which were reached again more cheaply
+            if self.settled.contains(&state) || self.best[&state] < cost {
+                continue;
+            }
+            self.settled.insert(state.clone());
+            for (next, step) in (self.successors)(&state) {
+                if !self.settled.contains(&next) {
+                    self.push(next, cost + step, Some(&state));
+                }
+            }
+            self.done = self.goal.as_ref().is_some_and(|goal| goal(&state));
+            return Some((state, cost));
+        }
+        None
+    }
+}
+
+/// Iterator over the states of a breadth-first search in the order they are reached
+pub struct BfsVisit<'a, S, F> {
+    successors: F,
+    goal: Option<Goal<'a, S>>,
+    costs: HashMap<S, usize>,
+    queue: VecDeque<(S, usize)>,
+    predecessors: Option<Predecessors<S>>,
+    done: bool,
+}
+
+impl<S, F, I> Iterator for BfsVisit<'_, S, F>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    type Item = (S, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let (state, cost) = self.queue.pop_front()?;
+        for next in (self.successors)(&state) {
+            match self.costs.get(&next) {
+                None => {
+                    record_step(&mut self.predecessors, &next, Some(&state), true);
+                    self.costs.insert(next.clone(), cost + 1);
+                    self.queue.push_back((next, cost + 1));
+                }
+                Some(&next_cost) if next_cost == cost + 1 => {
+                    record_step(&mut self.predecessors, &next, Some(&state), false);
+                }
+                _ => {}
+            }
+        }
+        self.done = self.goal.as_ref().is_some_and(|goal| goal(&state));
+        Some((state, cost))
+    }
+}
+
+/// Costs of the states settled by a search, and the goal state if one was reached. If paths were
+/// recorded, the predecessors of each state form a DAG of every shortest path from the starts.
+pub struct SearchResult<S, C> {
+    costs: HashMap<S, C>,
+    order: Vec<S>,
+    goal: Option<S>,
+    predecessors: Option<Predecessors<S>>,
+}
+
+impl<S, C> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy,
+{
+    fn from_visit<V>(visit: &mut V) -> Self
+    where
+        V: Iterator<Item = (S, C)>,
+    {
+        let mut costs = HashMap::new();
+        let mut order = Vec::new();
+        for (state, cost) in visit {
+            costs.insert(state.clone(), cost);
+            order.push(state);
+        }
+        Self {
+            costs,
+            order,
+            goal: None,
+            predecessors: None,
+        }
+    }
+
+    // the last state settled is the goal if the search stopped early
+    fn with_goal(mut self, reached_goal: bool) -> Self {
+        if reached_goal {
+            self.goal = self.order.last().cloned();
+        }
+        self
+    }
+
+    fn with_predecessors(mut self, predecessors: Option<Predecessors<S>>) -> Self {
+        self.predecessors = predecessors;
+        self
+    }
+
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.costs.get(state).copied()
+    }
+
+    /// The goal state and its cost, if the search stopped at one
+    #[allow(unused)]
+    pub fn goal(&self) -> Option<(&S, C)> {
+        self.goal.as_ref().map(|state| (state, self.costs[state]))
+    }
+
+    #[allow(unused)]
+    pub fn goal_cost(&self) -> Option<C> {
+        self.goal().map(|(_, cost)| cost)
+    }
+
+    /// States in the order they were settled, with their costs
+    #[allow(unused)]
+    pub fn visited(&self) -> impl Iterator<Item = (&S, C)> {
+        self.order.iter().map(|state| (state, self.costs[state]))
+    }
+
+    #[allow(unused)]
+    pub fn into_costs(self) -> HashMap<S, C> {
+        self.costs
+    }
+
+    /// The states from which a state is reached along a shortest path; empty for the starts and
+    /// for states which were not settled
+    pub fn predecessors(&self, state: &S) -> &[S] {
+        let predecessors = self
+            .predecessors
+            .as_ref()
+            .expect("SearchResult::predecessors: paths were not recorded");
+        match predecessors.get(state) {
+            Some(states) if self.costs.contains_key(state) => states,
+            _ => &[],
+        }
+    }
+
+    /// One shortest path from a start to the target, including both ends
+    pub fn path_to(&self, target: &S) -> Option<Vec<S>> {
+        self.costs.get(target)?;
+        let mut path = vec![target.clone()];
+        while let Some(previous) = self.predecessors(path.last().unwrap()).first() {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every shortest path from a start to any of the targets, as a map from each state on those
+    /// paths to its predecessors
+    pub fn paths_to<I>(&self, targets: I) -> Predecessors<S>
+    where
+        I: IntoIterator<Item = S>,
+    {
+        let mut dag = HashMap::new();
+        let mut stack = targets
+            .into_iter()
+            .filter(|target| self.costs.contains_key(target))
+            .collect::<Vec<_>>();
+        while let Some(state) = stack.pop() {
+            if dag.contains_key(&state) {
+                continue;
+            }
+            let predecessors = self.predecessors(&state).to_vec();
+            stack.extend(predecessors.iter().cloned());
+            dag.insert(state, predecessors);
+        }
+        dag
+    }
+
+    /// The states which lie on at least one shortest path to any of the targets
+    pub fn on_shortest_paths<I>(&self, targets: I) -> HashSet<S>
+    where
+        I: IntoIterator<Item = S>,
+    {
+        self.paths_to(targets).into_keys().collect()
+    }
+
+    /// The number of distinct shortest paths from any start to the target
+    #[allow(unused)]
+    pub fn path_count(&self, target: &S) -> Option<usize> {
+        self.costs.get(target)?;
+        // states are settled after all of their predecessors, so each count is complete by the
+        // time it is needed
+        let mut counts = HashMap::new();
+        for state in &self.order {
+            let predecessors = self.predecessors(state);
+            let count = if predecessors.is_empty() {
+                1
+            } else {
+                predecessors.iter().map(|previous| counts[previous]).sum()
+            };
+            if state == target {
+                return Some(count);
+            }
+            counts.insert(state, count);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a 3x3 grid of states, where each step moves right or down
+    fn lattice(&(i, j): &(usize, usize)) -> Vec<(usize, usize)> {
+        let mut next = Vec::new();
+        if i < 2 {
+            next.push((i + 1, j));
+        }
+        if j < 2 {
+            next.push((i, j + 1));
+        }
+        next
+    }
+
+    // an open 10x10 grid of states, where each step moves to an orthogonal neighbor
+    fn open_grid(&(i, j): &(usize, usize)) -> Vec<(usize, usize)> {
+        let mut next = Vec::new();
+        if i > 0 {
+            next.push((i - 1, j));
+        }
+        if i < 9 {
+            next.push((i + 1, j));
+        }
+        if j > 0 {
+            next.push((i, j - 1));
+        }
+        if j < 9 {
+            next.push((i, j + 1));
+        }
+        next
+    }
+
+    #[test]
+    fn bfs() {
+        let order = Search::new()
+            .starts([(0, 0), (9, 9)])
+            .iter_bfs(open_grid)
+            .collect::<Vec<_>>();
+        assert_eq!(order.len(), 100);
+        assert!(order.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        let result = Search::new().starts([(0, 0), (9, 9)]).bfs(open_grid);
+        assert_eq!(result.cost(&(0, 9)), Some(9));
+        assert_eq!(result.cost(&(4, 4)), Some(8));
+        assert_eq!(result.cost(&(5, 5)), Some(8));
+
+        // the dense search agrees, with states numbered by row and column
+        let costs = Search::new().starts([0, 99]).bfs_dense(100, |n| {
+            open_grid(&(n / 10, n % 10))
+                .into_iter()
+                .map(|(i, j)| i * 10 + j)
+        });
+        assert!(
+            costs
+                .iter()
+                .enumerate()
+                .all(|(n, &cost)| { cost == result.cost(&(n / 10, n % 10)) })
+        );
+    }
+
+    #[test]
+    fn dijkstra() {
+        // the direct edge from 0 to 3 costs more than the long way around
+        let edges = [(0, 1, 1), (1, 2, 1), (2, 3, 1), (0, 3, 5), (3, 4, 2)];
+        let successors = |&state: &usize| {
+            edges
+                .iter()
+                .filter(move |&&(from, _, _)| from == state)
+                .map(|&(_, to, cost)| (to, cost))
+        };
+        let order = Search::new().start(0).iter(successors).collect::<Vec<_>>();
+        assert_eq!(order, [(0, 0), (1, 1), (2, 2), (3, 3), (4, 5)]);
+        let result = Search::new()
+            .start(0)
+            .goal(|&state| state == 3)
+            .run(successors);
+        assert_eq!(result.goal(), Some((&3, 3)));
+        assert_eq!(result.cost(&4), None);
+
+        let costs = Search::new()
+            .start(0)
+            .run_dense(6, |state| successors(&state));
+        assert_eq!(costs, [Some(0), Some(1), Some(2), Some(3), Some(5), None]);
+        let costs = Search::new()
+            .start(0)
+            .goal(|&state| state == 3)
+            .run_dense(6, |state| successors(&state));
+        assert_eq!(costs, [Some(0), Some(1), Some(2), Some(3), None, None]);
+    }
+
+    #[test]
+    fn a_star() {
+        let target = (9, 9);
+        let manhattan = move |&(i, j): &(usize, usize)| (target.0 - i) + (target.1 - j);
+        let steps = |state: &(usize, usize)| open_grid(state).into_iter().map(|next| (next, 1));
+        let dijkstra = Search::new()
+            .start((0, 0))
+            .goal(move |&state| state == target)
+            .run(steps);
+        let a_star = Search::new()
+            .start((0, 0))
+            .goal(move |&state| state == target)
+            .heuristic(manhattan)
+            .run(steps);
+        assert_eq!(dijkstra.goal_cost(), Some(18));
+        assert_eq!(a_star.goal_cost(), Some(18));
+        // the heuristic keeps the search from wandering away from the target
+        assert!(a_star.visited().count() < dijkstra.visited().count());
+
+        let costs = Search::new()
+            .start(0)
+            .goal(|&n| n == 99)
+            .heuristic(|&n: &usize| manhattan(&(n / 10, n % 10)))
+            .run_dense(100, |n| {
+                steps(&(n / 10, n % 10)).map(|((i, j), cost)| (i * 10 + j, cost))
+            });
+        assert_eq!(costs[99], Some(18));
+        assert!(costs.iter().flatten().count() < 100);
+    }
+
+    #[test]
+    fn bfs_paths() {
+        let result = Search::new().start((0, 0)).record_paths().bfs(lattice);
+        assert_eq!(result.cost(&(2, 2)), Some(4));
+        assert_eq!(result.path_count(&(2, 2)), Some(6));
+        assert_eq!(result.path_count(&(1, 2)), Some(3));
+        let path = result.path_to(&(2, 2)).unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!((path[0], path[4]), ((0, 0), (2, 2)));
+        assert_eq!(result.on_shortest_paths([(1, 1)]).len(), 4);
+        assert_eq!(result.paths_to([(0, 1)])[&(0, 1)], vec![(0, 0)]);
+    }
+
+    #[test]
+    fn dijkstra_paths() {
+        // moving down costs more than moving right, but every path to a state takes the same steps
+        let result = Search::new().start((0, 0)).record_paths().run(|&(i, j)| {
+            lattice(&(i, j))
+                .into_iter()
+                .map(move |(ii, jj)| ((ii, jj), if ii > i { 2 } else { 1 }))
+        });
+        assert_eq!(result.cost(&(2, 2)), Some(6));
+        assert_eq!(result.path_count(&(2, 2)), Some(6));
+        assert_eq!(result.predecessors(&(0, 0)), &[] as &[(usize, usize)]);
+        assert_eq!(result.on_shortest_paths([(2, 2)]).len(), 9);
+    }
+
+    #[test]
+    fn goal_stops_search() {
+        let result = Search::new()
+            .start((0, 0))
+            .goal(|&state| state == (1, 0))
+            .record_paths()
+            .bfs(lattice);
+        assert_eq!(result.goal(), Some((&(1, 0), 1)));
+        assert_eq!(result.path_to(&(1, 0)), Some(vec![(0, 0), (1, 0)]));
+        assert_eq!(result.cost(&(2, 2)), None);
+    }
+}
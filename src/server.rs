@@ -0,0 +1,482 @@
+/*
+** src/server.rs
+*/
+
+use crate::puzzles::{Answer, Puzzle, PuzzleIterator, PuzzleModules, Solution};
+
+use log::{debug, info, warn};
+
+use std::io::{self, BufReader, prelude::*};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const MAX_HEADER_SIZE: usize = 8 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy)]
+pub struct ServerConfig {
+    pub port: u16,
+    pub max_body_size: usize,
+    pub timeout: Duration,
+    pub max_solvers: usize,
+    pub max_connections: usize,
+}
+
+/// Number of solver threads still running. A solver which times out cannot be stopped, so it
+/// keeps its slot until it finishes, which stops slow requests from piling up threads.
+struct SolverLimit {
+    running: AtomicUsize,
+    max: usize,
+}
+
+impl SolverLimit {
+    fn new(max: usize) -> Arc<Self> {
+        Arc::new(Self {
+            running: AtomicUsize::new(0),
+            max,
+        })
+    }
+
+    fn acquire(self: &Arc<Self>) -> Option<SolverSlot> {
+        self.running
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |running| {
+                (running < self.max).then_some(running + 1)
+            })
+            .ok()
+            .map(|_| SolverSlot(Arc::clone(self)))
+    }
+}
+
+/// A running solver, which frees its slot when dropped, even if the solver panics
+struct SolverSlot(Arc<SolverLimit>);
+
+impl Drop for SolverSlot {
+    fn drop(&mut self) {
+        self.0.running.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn json(status: u16, body: String) -> Self {
+        Self { status, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, format!("{{\"error\":{}}}", json_string(message)))
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            411 => "Length Required",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            _ => "Internal Server Error",
+        }
+    }
+
+    fn write_to(&self, stream: &mut TcpStream) -> io::Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            self.body.len(),
+            self.body
+        )?;
+        stream.flush()
+    }
+}
+
+/// Escapes a string as a JSON string literal
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn json_answer(answer: &Option<Answer>) -> String {
+    match answer {
+        None => "null".to_string(),
+        Some(Answer::String(s)) => json_string(s),
+        Some(answer) => answer.to_string(),
+    }
+}
+
+fn read_request<R: Read>(stream: R, max_body_size: usize) -> Result<Request, Response> {
+    let mut reader = BufReader::new(stream);
+    let mut header_size = 0;
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        let n = reader
+            .by_ref()
+            .take((MAX_HEADER_SIZE - header_size) as u64)
+            .read_line(&mut line)
+            .map_err(|_| Response::error(400, "malformed request"))?;
+        header_size += n;
+        if !line.ends_with('\n') {
+            return if header_size >= MAX_HEADER_SIZE {
+                Err(Response::error(431, "request headers too large"))
+            } else {
+                Err(Response::error(400, "malformed request"))
+            };
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            break;
+        }
+        lines.push(line.to_string());
+    }
+
+    let request_line = lines
+        .first()
+        .ok_or_else(|| Response::error(400, "missing request line"))?;
+    let mut parts = request_line.split_ascii_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Err(Response::error(400, "malformed request line")),
+    };
+
+    let mut content_length = None;
+    for header in lines.iter().skip(1) {
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            let length = value
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| Response::error(400, "invalid Content-Length"))?;
+            content_length = Some(length);
+        }
+    }
+
+    let body = match content_length {
+        None if method == "POST" => return Err(Response::error(411, "missing Content-Length")),
+        None | Some(0) => Vec::new(),
+        Some(length) if length > max_body_size => {
+            return Err(Response::error(413, "request body too large"));
+        }
+        Some(length) => {
+            let mut body = vec![0; length];
+            reader
+                .read_exact(&mut body)
+                .map_err(|_| Response::error(400, "truncated request body"))?;
+            body
+        }
+    };
+
+    Ok(Request { method, path, body })
+}
+
+fn list_puzzles() -> Response {
+    let puzzles = PuzzleIterator::all()
+        .map(|puzzle| format!("{{\"year\":{},\"day\":{}}}", puzzle.year, puzzle.day + 1))
+        .collect::<Vec<_>>()
+        .join(",");
+    Response::json(200, format!("{{\"puzzles\":[{}]}}", puzzles))
+}
+
+fn parse_puzzle(year: &str, day: &str) -> Option<Puzzle> {
+    let year = year.parse::<usize>().ok()?;
+    let day = day.parse::<usize>().ok()?.checked_sub(1)?;
+    let puzzle = Puzzle::new(year, day);
+    PuzzleIterator::all().any(|p| p == puzzle).then_some(puzzle)
+}
+
+/// Runs the solver on a separate thread so that a bad input cannot hang the server
+fn solve_with_timeout(
+    puzzle: Puzzle,
+    input: String,
+    timeout: Duration,
+    slot: SolverSlot,
+) -> Result<(Solution, f64), RecvTimeoutError> {
+    let solver = PuzzleModules::dispatch(puzzle.year, puzzle.day);
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let t = Instant::now();
+        let solution = (solver)(input);
+        let duration_ms = t.elapsed().as_secs_f64() * 1000.0;
+        // free the slot before replying, so a client sending its next request as soon as this
+        // one is answered is not turned away
+        drop(slot);
+        // the receiver has gone away if the solver timed out
+        let _ = tx.send((solution, duration_ms));
+    });
+    rx.recv_timeout(timeout)
+}
+
+fn solve_puzzle(
+    puzzle: Puzzle,
+    body: Vec<u8>,
+    timeout: Duration,
+    solvers: &Arc<SolverLimit>,
+) -> Response {
+    let input = match String::from_utf8(body) {
+        Ok(input) => input,
+        Err(_) => return Response::error(400, "input is not valid UTF-8"),
+    };
+    // puzzle inputs on disk have no trailing newline
+    let input = input.trim_end_matches(['\r', '\n']).to_string();
+
+    let Some(slot) = solvers.acquire() else {
+        warn!("{}: too many solvers running", puzzle);
+        return Response::error(503, "too many solvers running");
+    };
+    match solve_with_timeout(puzzle, input, timeout, slot) {
+        Ok((solution, duration_ms)) => {
+            info!("{}: solved in {:.03}ms", puzzle, duration_ms);
+            Response::json(
+                200,
+                format!(
+                    "{{\"year\":{},\"day\":{},\"part_a\":{},\"part_b\":{},\"time_ms\":{:.3}}}",
+                    puzzle.year,
+                    puzzle.day + 1,
+                    json_answer(&solution.part_a),
+                    json_answer(&solution.part_b),
+                    duration_ms
+                ),
+            )
+        }
+        Err(RecvTimeoutError::Timeout) => {
+            warn!("{}: timed out after {}ms", puzzle, timeout.as_millis());
+            Response::error(504, "solver timed out")
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+            warn!("{}: solver failed", puzzle);
+            Response::error(422, "solver failed on the given input")
+        }
+    }
+}
+
+fn route(request: Request, config: &ServerConfig, solvers: &Arc<SolverLimit>) -> Response {
+    let segments = request
+        .path
+        .trim_matches('/')
+        .split('/')
+        .collect::<Vec<_>>();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["puzzles"]) => list_puzzles(),
+        ("POST", ["solve", year, day]) => match parse_puzzle(year, day) {
+            Some(puzzle) => solve_puzzle(puzzle, request.body, config.timeout, solvers),
+            None => Response::error(404, "no such puzzle"),
+        },
+        (_, ["puzzles"]) | (_, ["solve", _, _]) => Response::error(405, "method not allowed"),
+        _ => Response::error(404, "not found"),
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, config: &ServerConfig, solvers: &Arc<SolverLimit>) {
+    let response = match read_request(&mut stream, config.max_body_size) {
+        Ok(request) => {
+            debug!("{} {}", request.method, request.path);
+            route(request, config, solvers)
+        }
+        Err(response) => response,
+    };
+    if let Err(err) = response.write_to(&mut stream) {
+        warn!("failed to write response: {}", err);
+    }
+}
+
+// takes connections off the queue until the server stops accepting them
+fn connection_worker(
+    connections: &Mutex<Receiver<TcpStream>>,
+    config: &ServerConfig,
+    solvers: &Arc<SolverLimit>,
+) {
+    loop {
+        let Ok(stream) = connections.lock().unwrap().recv() else {
+            return;
+        };
+        handle_connection(stream, config, solvers);
+    }
+}
+
+pub fn serve(config: ServerConfig) -> io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", config.port))?;
+    info!("Listening on http://{}", listener.local_addr()?);
+
+    // a fixed pool of workers handles connections, so clients which connect and then go quiet
+    // can only tie up that many threads, and only as many again can wait for a worker
+    let solvers = SolverLimit::new(config.max_solvers);
+    let (queue, connections) = mpsc::sync_channel(config.max_connections);
+    let connections = Arc::new(Mutex::new(connections));
+    for _ in 0..config.max_connections {
+        let connections = Arc::clone(&connections);
+        let solvers = Arc::clone(&solvers);
+        thread::spawn(move || connection_worker(&connections, &config, &solvers));
+    }
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                warn!("failed to accept connection: {}", err);
+                continue;
+            }
+        };
+        // the timeouts apply while the connection waits in the queue as well
+        let timeouts = stream
+            .set_read_timeout(Some(READ_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(WRITE_TIMEOUT)));
+        if let Err(err) = timeouts {
+            warn!("failed to set timeouts: {}", err);
+        }
+        if let Err(TrySendError::Full(mut stream)) = queue.try_send(stream) {
+            warn!("too many connections");
+            let response = Response::error(503, "too many connections");
+            if let Err(err) = response.write_to(&mut stream) {
+                warn!("failed to write response: {}", err);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: ServerConfig = ServerConfig {
+        port: 0,
+        max_body_size: 64,
+        timeout: Duration::from_secs(10),
+        max_solvers: 1,
+        max_connections: 1,
+    };
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn parse_request() {
+        let raw =
+            "POST /solve/2020/1 HTTP/1.1\r\nHost: localhost\r\ncontent-length: 5\r\n\r\nhello";
+        let request = read_request(raw.as_bytes(), 64).ok().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/solve/2020/1");
+        assert_eq!(request.body, b"hello");
+
+        let request = read_request("GET /puzzles HTTP/1.1\n\n".as_bytes(), 64)
+            .ok()
+            .unwrap();
+        assert_eq!((request.method.as_str(), request.body.len()), ("GET", 0));
+
+        let status = |raw: &str| read_request(raw.as_bytes(), 64).err().unwrap().status;
+        assert_eq!(status("GET\r\n\r\n"), 400);
+        assert_eq!(status("GET /puzzles HTTP/1.1\r\n"), 400);
+        assert_eq!(status("POST /solve/2020/1 HTTP/1.1\r\n\r\n"), 411);
+        assert_eq!(status("POST / HTTP/1.1\r\nContent-Length: x\r\n\r\n"), 400);
+        assert_eq!(
+            status("POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nshort"),
+            400
+        );
+    }
+
+    #[test]
+    fn request_limits() {
+        let status = |raw: &str| read_request(raw.as_bytes(), 64).err().unwrap().status;
+        assert_eq!(status("POST / HTTP/1.1\r\nContent-Length: 65\r\n\r\n"), 413);
+        let header = format!("X-Padding: {}\r\n", "a".repeat(MAX_HEADER_SIZE));
+        assert_eq!(status(&format!("GET / HTTP/1.1\r\n{}\r\n", header)), 431);
+        let body = "a".repeat(64);
+        let raw = format!("POST / HTTP/1.1\r\nContent-Length: 64\r\n\r\n{}", body);
+        assert!(read_request(raw.as_bytes(), 64).is_ok());
+    }
+
+    #[test]
+    fn routing() {
+        let solvers = SolverLimit::new(CONFIG.max_solvers);
+        let status = |method, path, body| route(request(method, path, body), &CONFIG, &solvers);
+        let response = status("GET", "/puzzles", "");
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains("{\"year\":2020,\"day\":1}"));
+        assert_eq!(status("POST", "/puzzles", "").status, 405);
+        assert_eq!(status("GET", "/solve/2020/1", "").status, 405);
+        assert_eq!(status("POST", "/solve/2020/0", "").status, 404);
+        assert_eq!(status("POST", "/solve/1999/1", "").status, 404);
+        assert_eq!(status("GET", "/nowhere", "").status, 404);
+        let invalid = Request {
+            body: vec![0xff],
+            ..request("POST", "/solve/2020/1", "")
+        };
+        assert_eq!(route(invalid, &CONFIG, &solvers).status, 400);
+
+        let response = status("POST", "/solve/2020/1", "1721\n979\n366\n299\n675\n1456\n");
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains("\"part_a\":514579"));
+        assert!(response.body.contains("\"part_b\":241861950"));
+
+        // every solver slot is taken
+        let _slot = solvers.acquire().unwrap();
+        assert!(solvers.acquire().is_none());
+        assert_eq!(status("POST", "/solve/2020/1", "1721").status, 503);
+    }
+
+    #[test]
+    fn connection_worker_drains_queue() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let address = listener.local_addr().unwrap();
+        let (queue, connections) = mpsc::sync_channel(2);
+        let mut clients = Vec::new();
+        for _ in 0..2 {
+            let mut client = TcpStream::connect(address).unwrap();
+            client.write_all(b"GET /puzzles HTTP/1.1\r\n\r\n").unwrap();
+            queue.try_send(listener.accept().unwrap().0).unwrap();
+            clients.push(client);
+        }
+        // a third connection finds the queue full
+        let _extra = TcpStream::connect(address).unwrap();
+        let full = queue.try_send(listener.accept().unwrap().0);
+        assert!(matches!(full, Err(TrySendError::Full(_))));
+
+        // the worker answers the queued connections and returns once the queue is closed
+        drop(queue);
+        connection_worker(&Mutex::new(connections), &CONFIG, &SolverLimit::new(1));
+        for mut client in clients {
+            let mut response = String::new();
+            client.read_to_string(&mut response).unwrap();
+            assert!(response.starts_with("HTTP/1.1 200 OK"));
+        }
+    }
+}
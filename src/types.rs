@@ -2,19 +2,23 @@
 ** src/types.rs
 */
 
+use super::search::Search;
 use super::utils;
 
 use anyhow::Error;
 use case_iterable::CaseIterable;
-use nalgebra::{Matrix2, Vector2};
 
-use std::cmp::{self, Ordering};
-use std::collections::{BinaryHeap, HashMap};
-use std::hash::Hash;
-use std::ops::Sub;
+use std::cmp;
+use std::collections::{HashMap, VecDeque, hash_map};
+use std::hash::{Hash, Hasher};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Range, Sub, SubAssign};
+use std::sync::Arc;
 
+/// Any of the eight directions to a neighboring cell, including diagonals, which converts to and
+/// from Cardinal for the four along the axes
 #[derive(CaseIterable, Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum Direction {
+pub enum Octile {
     North,
     South,
     East,
@@ -25,15 +29,9 @@ pub enum Direction {
     SouthWest,
 }
 
-impl Direction {
+impl Octile {
     pub fn cardinal() -> impl Iterator<Item = Self> {
-        vec![
-            Direction::North,
-            Direction::South,
-            Direction::East,
-            Direction::West,
-        ]
-        .into_iter()
+        [Octile::North, Octile::South, Octile::East, Octile::West].into_iter()
     }
 
     pub fn grid_delta(&self) -> (i64, i64) {
@@ -49,29 +47,267 @@ impl Direction {
         }
     }
 
-    pub fn turn_90_clockwise(&self) -> Self {
+    /// Offset of a single step on a plane where x is the column and y is the row
+    pub fn point_delta(&self) -> Point {
+        let (di, dj) = self.grid_delta();
+        Point::new(dj, di)
+    }
+
+    /// Position clockwise from North, in steps of 45 degrees
+    fn octant(&self) -> i64 {
         match self {
-            Self::North => Self::East,
-            Self::East => Self::South,
-            Self::South => Self::West,
-            Self::West => Self::North,
-            Self::NorthEast => Self::SouthEast,
-            Self::NorthWest => Self::NorthEast,
-            Self::SouthEast => Self::SouthWest,
-            Self::SouthWest => Self::NorthWest,
+            Self::North => 0,
+            Self::NorthEast => 1,
+            Self::East => 2,
+            Self::SouthEast => 3,
+            Self::South => 4,
+            Self::SouthWest => 5,
+            Self::West => 6,
+            Self::NorthWest => 7,
+        }
+    }
+
+    fn from_octant(octant: i64) -> Self {
+        match octant.rem_euclid(8) {
+            0 => Self::North,
+            1 => Self::NorthEast,
+            2 => Self::East,
+            3 => Self::SouthEast,
+            4 => Self::South,
+            5 => Self::SouthWest,
+            6 => Self::West,
+            _ => Self::NorthWest,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn is_cardinal(&self) -> bool {
+        self.octant() % 2 == 0
+    }
+
+    /// Turns clockwise by a multiple of 45 degrees, or counter-clockwise if negative
+    pub fn rotate(&self, degrees: i64) -> Self {
+        if degrees % 45 != 0 {
+            panic!("Octile::rotate: not a multiple of 45 degrees: {}", degrees);
         }
+        Self::from_octant(self.octant() + (degrees / 45))
     }
 
+    pub fn turn_90_clockwise(&self) -> Self {
+        self.rotate(90)
+    }
+
+    #[allow(unused)]
     pub fn turn_90_counterclockwise(&self) -> Self {
+        self.rotate(-90)
+    }
+
+    #[allow(unused)]
+    pub fn opposite(&self) -> Self {
+        self.rotate(180)
+    }
+
+    /// Clockwise angle in degrees to turn from this heading to the other, from 0 up to 315
+    #[allow(unused)]
+    pub fn angle_to(&self, other: &Self) -> i64 {
+        (other.octant() - self.octant()).rem_euclid(8) * 45
+    }
+}
+
+impl TryFrom<char> for Octile {
+    type Error = Error;
+
+    /// Parses arrows, UDLR or compass letters
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '^' | 'U' | 'N' => Result::Ok(Self::North),
+            'v' | 'D' | 'S' => Result::Ok(Self::South),
+            '>' | 'R' | 'E' => Result::Ok(Self::East),
+            '<' | 'L' | 'W' => Result::Ok(Self::West),
+            _ => Result::Err(Error::msg(format!("invalid direction: {}", value))),
+        }
+    }
+}
+
+/// One of the four directions along the axes of a grid
+#[derive(CaseIterable, Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Cardinal {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Cardinal {
+    #[allow(unused)]
+    pub fn grid_delta(&self) -> (i64, i64) {
+        Octile::from(*self).grid_delta()
+    }
+
+    /// Offset of a single step on a plane where x is the column and y is the row
+    pub fn point_delta(&self) -> Point {
+        Octile::from(*self).point_delta()
+    }
+
+    /// Turns clockwise by a multiple of 90 degrees, or counter-clockwise if negative
+    pub fn rotate(&self, degrees: i64) -> Self {
+        if degrees % 90 != 0 {
+            panic!(
+                "Cardinal::rotate: not a multiple of 90 degrees: {}",
+                degrees
+            );
+        }
+        Self::try_from(Octile::from(*self).rotate(degrees)).unwrap()
+    }
+
+    #[allow(unused)]
+    pub fn opposite(&self) -> Self {
+        self.rotate(180)
+    }
+
+    /// Clockwise angle in degrees to turn from this heading to the other, from 0 up to 270
+    #[allow(unused)]
+    pub fn angle_to(&self, other: &Self) -> i64 {
+        Octile::from(*self).angle_to(&Octile::from(*other))
+    }
+}
+
+impl From<Cardinal> for Octile {
+    fn from(value: Cardinal) -> Self {
+        match value {
+            Cardinal::North => Self::North,
+            Cardinal::East => Self::East,
+            Cardinal::South => Self::South,
+            Cardinal::West => Self::West,
+        }
+    }
+}
+
+impl TryFrom<Octile> for Cardinal {
+    type Error = Error;
+
+    fn try_from(value: Octile) -> Result<Self, Self::Error> {
+        match value {
+            Octile::North => Result::Ok(Self::North),
+            Octile::East => Result::Ok(Self::East),
+            Octile::South => Result::Ok(Self::South),
+            Octile::West => Result::Ok(Self::West),
+            _ => Result::Err(Error::msg(format!("not a cardinal direction: {:?}", value))),
+        }
+    }
+}
+
+impl TryFrom<char> for Cardinal {
+    type Error = Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Self::try_from(Octile::try_from(value)?)
+    }
+}
+
+/// One of the six directions to a neighboring cell of a hex grid with pointy tops, using axial
+/// coordinates where x increases to the east and y increases to the south-east
+#[derive(CaseIterable, Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HexDirection {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+#[allow(unused)]
+impl HexDirection {
+    /// Position clockwise from East, in steps of 60 degrees
+    fn sextant(&self) -> i64 {
+        match self {
+            Self::East => 0,
+            Self::SouthEast => 1,
+            Self::SouthWest => 2,
+            Self::West => 3,
+            Self::NorthWest => 4,
+            Self::NorthEast => 5,
+        }
+    }
+
+    /// Offset of a single step in axial coordinates
+    pub fn point_delta(&self) -> Point {
+        match self {
+            Self::East => Point::new(1, 0),
+            Self::SouthEast => Point::new(0, 1),
+            Self::SouthWest => Point::new(-1, 1),
+            Self::West => Point::new(-1, 0),
+            Self::NorthWest => Point::new(0, -1),
+            Self::NorthEast => Point::new(1, -1),
+        }
+    }
+
+    /// Turns clockwise by a multiple of 60 degrees, or counter-clockwise if negative
+    pub fn rotate(&self, degrees: i64) -> Self {
+        if degrees % 60 != 0 {
+            panic!(
+                "HexDirection::rotate: not a multiple of 60 degrees: {}",
+                degrees
+            );
+        }
+        let sextant = (self.sextant() + (degrees / 60)).rem_euclid(6);
+        Self::all_cases().find(|d| d.sextant() == sextant).unwrap()
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate(180)
+    }
+}
+
+impl TryFrom<&str> for HexDirection {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "e" => Result::Ok(Self::East),
+            "se" => Result::Ok(Self::SouthEast),
+            "sw" => Result::Ok(Self::SouthWest),
+            "w" => Result::Ok(Self::West),
+            "nw" => Result::Ok(Self::NorthWest),
+            "ne" => Result::Ok(Self::NorthEast),
+            _ => Result::Err(Error::msg(format!("invalid hex direction: {}", value))),
+        }
+    }
+}
+
+/// One of the six directions along the axes of a 3D grid
+#[derive(CaseIterable, Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction3D {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+#[allow(unused)]
+impl Direction3D {
+    pub fn point_delta(&self) -> Point3D {
         match self {
-            Self::North => Self::West,
-            Self::West => Self::South,
-            Self::South => Self::East,
-            Self::East => Self::North,
-            Self::NorthEast => Self::NorthWest,
-            Self::NorthWest => Self::SouthWest,
-            Self::SouthWest => Self::SouthEast,
-            Self::SouthEast => Self::NorthEast,
+            Self::PositiveX => Point3D::new([1, 0, 0]),
+            Self::NegativeX => Point3D::new([-1, 0, 0]),
+            Self::PositiveY => Point3D::new([0, 1, 0]),
+            Self::NegativeY => Point3D::new([0, -1, 0]),
+            Self::PositiveZ => Point3D::new([0, 0, 1]),
+            Self::NegativeZ => Point3D::new([0, 0, -1]),
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::PositiveX => Self::NegativeX,
+            Self::NegativeX => Self::PositiveX,
+            Self::PositiveY => Self::NegativeY,
+            Self::NegativeY => Self::PositiveY,
+            Self::PositiveZ => Self::NegativeZ,
+            Self::NegativeZ => Self::PositiveZ,
         }
     }
 }
@@ -114,10 +350,10 @@ impl Point {
         dx.abs() + dy.abs()
     }
 
-    pub fn ccw(a: &Point, b: &Point, c: &Point) -> bool {
-        // Are the 3 points listed in counter-clockwise order?
-        // If the slope of the line AB is less than the slope of the line AC
-        (c.y - a.y) * (b.x - a.x) > (b.y - a.y) * (c.x - a.x)
+    /// Cross product of the vectors from o to a and from o to b, which is positive if o, a and b
+    /// turn counter-clockwise, negative if clockwise and zero if they are collinear
+    pub fn cross(o: Point, a: Point, b: Point) -> i64 {
+        ((a.x - o.x) * (b.y - o.y)) - ((a.y - o.y) * (b.x - o.x))
     }
 }
 
@@ -157,72 +393,260 @@ impl TryFrom<&str> for Point {
     }
 }
 
-#[derive(Debug)]
-pub struct Point3D {
-    pub x: i64,
-    pub y: i64,
-    pub z: i64,
+/// Point or vector with D integer coordinates
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PointN<const D: usize> {
+    pub coords: [i64; D],
+}
+
+#[allow(unused)]
+pub type Point2D = PointN<2>;
+pub type Point3D = PointN<3>;
+#[allow(unused)]
+pub type Point4D = PointN<4>;
+
+impl<const D: usize> PointN<D> {
+    pub fn new(coords: [i64; D]) -> Self {
+        Self { coords }
+    }
+
+    #[allow(unused)]
+    pub fn origin() -> Self {
+        Self::new([0; D])
+    }
+
+    #[allow(unused)]
+    pub fn manhattan_distance(a: Self, b: Self) -> i64 {
+        (a - b).coords.iter().map(|d| d.abs()).sum()
+    }
+
+    #[allow(unused)]
+    pub fn chebyshev_distance(a: Self, b: Self) -> i64 {
+        (a - b).coords.iter().map(|d| d.abs()).max().unwrap_or(0)
+    }
+
+    /// Squared straight-line distance, which is exact for comparisons
+    pub fn squared_distance(a: Self, b: Self) -> i64 {
+        (a - b).coords.iter().map(|d| d * d).sum()
+    }
+
+    /// The 2 * D points one step away along a single axis
+    #[allow(unused)]
+    pub fn axis_neighbors(self) -> impl Iterator<Item = Self> {
+        (0..D).flat_map(move |axis| {
+            [-1, 1].map(|delta| {
+                let mut neighbor = self;
+                neighbor.coords[axis] += delta;
+                neighbor
+            })
+        })
+    }
+
+    /// The 3^D - 1 points at most one step away along every axis
+    pub fn moore_neighbors(self) -> impl Iterator<Item = Self> {
+        // each offset is a base 3 number with a digit per axis, where all 1s is the point itself
+        let count = 3usize.pow(D as u32);
+        (0..count)
+            .filter(move |&n| n != count / 2)
+            .map(move |mut n| {
+                let mut neighbor = self;
+                for coord in neighbor.coords.iter_mut() {
+                    *coord += (n % 3) as i64 - 1;
+                    n /= 3;
+                }
+                neighbor
+            })
+    }
+}
+
+impl PointN<2> {
+    #[allow(unused)]
+    pub fn x(&self) -> i64 {
+        self.coords[0]
+    }
+
+    #[allow(unused)]
+    pub fn y(&self) -> i64 {
+        self.coords[1]
+    }
+}
+
+impl PointN<3> {
+    pub fn x(&self) -> i64 {
+        self.coords[0]
+    }
+
+    #[allow(unused)]
+    pub fn y(&self) -> i64 {
+        self.coords[1]
+    }
+
+    #[allow(unused)]
+    pub fn z(&self) -> i64 {
+        self.coords[2]
+    }
+}
+
+impl From<Point> for PointN<2> {
+    fn from(value: Point) -> Self {
+        Self::new([value.x, value.y])
+    }
+}
+
+impl From<PointN<2>> for Point {
+    fn from(value: PointN<2>) -> Self {
+        Self::new(value.coords[0], value.coords[1])
+    }
+}
+
+impl<const D: usize> Index<usize> for PointN<D> {
+    type Output = i64;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.coords[axis]
+    }
+}
+
+impl<const D: usize> IndexMut<usize> for PointN<D> {
+    fn index_mut(&mut self, axis: usize) -> &mut Self::Output {
+        &mut self.coords[axis]
+    }
+}
+
+impl<const D: usize> Add for PointN<D> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(std::array::from_fn(|axis| {
+            self.coords[axis] + rhs.coords[axis]
+        }))
+    }
+}
+
+impl<const D: usize> Sub for PointN<D> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(std::array::from_fn(|axis| {
+            self.coords[axis] - rhs.coords[axis]
+        }))
+    }
 }
 
-impl Point3D {
-    pub fn new(x: i64, y: i64, z: i64) -> Self {
-        Self { x, y, z }
+impl<const D: usize> Mul<i64> for PointN<D> {
+    type Output = Self;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        Self::new(self.coords.map(|coord| coord * rhs))
     }
 }
 
-impl std::fmt::Display for Point3D {
+impl<const D: usize> std::fmt::Display for PointN<D> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({},{},{})", self.x, self.y, self.z)
+        write!(f, "(")?;
+        for (axis, coord) in self.coords.iter().enumerate() {
+            if axis > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", coord)?;
+        }
+        write!(f, ")")
     }
 }
 
-impl TryFrom<&str> for Point3D {
+impl<const D: usize> TryFrom<&str> for PointN<D> {
     type Error = Error;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let parts = value.split(',').collect::<Vec<_>>();
-        match parts.as_slice() {
-            [x_str, y_str, z_str] => {
-                let x = x_str.parse::<i64>()?;
-                let y = y_str.parse::<i64>()?;
-                let z = z_str.parse::<i64>()?;
-                Result::Ok(Self::new(x, y, z))
-            }
-            _ => {
-                Result::Err(Error::msg("invalid format"))
-            }
+        let mut coords = [0; D];
+        let mut parts = value.split(',');
+        for coord in coords.iter_mut() {
+            let part = parts.next().ok_or_else(|| Error::msg("invalid format"))?;
+            *coord = part.parse::<i64>()?;
+        }
+        if parts.next().is_some() {
+            return Result::Err(Error::msg("invalid format"));
+        }
+        Result::Ok(Self::new(coords))
+    }
+}
+
+/// Exact fraction, kept in lowest terms with a positive denominator
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Rational {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl Rational {
+    pub fn new(numerator: i64, denominator: i64) -> Self {
+        if denominator == 0 {
+            panic!("Rational::new: zero denominator");
+        }
+        let divisor = utils::gcd(numerator.unsigned_abs(), denominator.unsigned_abs()) as i64;
+        let sign = denominator.signum();
+        Self {
+            numerator: sign * numerator / divisor,
+            denominator: sign * denominator / divisor,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn numerator(&self) -> i64 {
+        self.numerator
+    }
+
+    #[allow(unused)]
+    pub fn denominator(&self) -> i64 {
+        self.denominator
+    }
+
+    /// The value if it is a whole number
+    pub fn to_integer(self) -> Option<i64> {
+        (self.denominator == 1).then_some(self.numerator)
+    }
+}
+
+impl std::fmt::Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
         }
     }
 }
 
-#[derive(Clone, Eq, Hash, PartialEq)]
+/// Line segment between two points, including both ends
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Line {
     pub p0: Point,
     pub p1: Point,
-    pub slope: Option<i64>,
-    pub y_intercept: Option<i64>,
+}
+
+/// Where two line segments meet
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Intersection {
+    Disjoint,
+    /// The segments cross or touch at a single point, which may not be a lattice point
+    Point(Rational, Rational),
+    /// The segments are collinear and share this segment
+    Overlap(Line),
+}
+
+impl Intersection {
+    /// The point where the segments meet, if it is a single lattice point
+    pub fn lattice_point(&self) -> Option<Point> {
+        match self {
+            Self::Point(x, y) => Some(Point::new(x.to_integer()?, y.to_integer()?)),
+            _ => None,
+        }
+    }
 }
 
 impl Line {
     pub fn new(p0: Point, p1: Point) -> Self {
-        let slope = if p0.x == p1.x {
-            None
-        } else {
-            let lp = cmp::min_by_key(p0, p1, |p| p.x);
-            let rp = if lp == p0 { p1 } else { p0 };
-            Some((rp.y - lp.y) / (rp.x - lp.x))
-        };
-        let y_intercept = if p0.x == p1.x {
-            None
-        } else {
-            Some(p0.y - (p0.x * slope.unwrap()))
-        };
-        Self {
-            p0,
-            p1,
-            slope,
-            y_intercept,
-        }
+        Self { p0, p1 }
     }
 
     pub fn is_horizontal(&self) -> bool {
@@ -249,46 +673,57 @@ impl Line {
         cmp::max(self.p0.y, self.p1.y)
     }
 
+    #[allow(unused)]
     pub fn contains_point(&self, p: &Point) -> bool {
-        if self.is_vertical() {
-            p.x == self.p0.x && (self.y_min()..=self.y_max()).contains(&p.y)
-        } else {
-            p.y == (self.slope.unwrap() * p.x) + self.y_intercept.unwrap()
-                && (self.x_min()..=self.x_max()).contains(&p.x)
-                && (self.y_min()..=self.y_max()).contains(&p.y)
-        }
-    }
-
-    fn has_intersection(a: &Self, b: &Self) -> bool {
-        // Note: the below does not cover scenarios when an endpoint is the intersection
-        a.contains_point(&b.p0) || a.contains_point(&b.p1)
-            || b.contains_point(&a.p0) || b.contains_point(&a.p1)
-        // See https://bryceboe.com/2006/10/23/line-segment-intersection-algorithm/
-        // Lines A and B intersect if and only if points A0 and A1 are separated by segment B0-B1
-        // and points B0 and B1 are separated by segment A0-A1 then: if A0 and A1 are separated by
-        // segment B0-B1 then A0-B0-B1 and A1-B0-B1 should have opposite orientation; i.e. either
-        // A0-B0-B1 or A1-B0-B1 is counter-clockwise but NOT both
-            || Point::ccw(&a.p0, &b.p0, &b.p1)
-            != Point::ccw(&a.p1, &b.p0, &b.p1)
-            && Point::ccw(&a.p0, &a.p1, &b.p0)
-                != Point::ccw(&a.p0, &a.p1, &b.p1)
-    }
-
-    pub fn intersection(a: &Self, b: &Self) -> Option<Point> {
-        if Self::has_intersection(a, b) {
-            // Solve the system of equations
-            let ma = a.slope? as f64;
-            let mb = b.slope? as f64;
-            let mat = Matrix2::new(-ma, 1.0, -mb, 1.0);
-            let vec = Vector2::new(a.y_intercept? as f64, b.y_intercept? as f64);
-            let sol = mat.try_inverse()? * vec;
-            let x = sol[0];
-            let y = sol[1];
-            if x.fract() == 0.0 && y.fract() == 0.0 {
-                return Some(Point::new(x.round() as i64, y.round() as i64));
+        Point::cross(self.p0, self.p1, *p) == 0
+            && (self.x_min()..=self.x_max()).contains(&p.x)
+            && (self.y_min()..=self.y_max()).contains(&p.y)
+    }
+
+    /// The lattice points along the segment, from p0 to p1
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        let (dx, dy) = (self.p1.x - self.p0.x, self.p1.y - self.p0.y);
+        let steps = utils::gcd(dx.unsigned_abs(), dy.unsigned_abs()) as i64;
+        let step = Point::new(dx / steps.max(1), dy / steps.max(1));
+        (0..=steps).map(move |n| Point::new(self.p0.x + (n * step.x), self.p0.y + (n * step.y)))
+    }
+
+    /// Exact intersection of two segments, found by solving a.p0 + t(a.p1 - a.p0) =
+    /// b.p0 + u(b.p1 - b.p0) for t and u in the range 0 to 1
+    pub fn intersection(a: &Self, b: &Self) -> Intersection {
+        let (p, q) = (a.p0, b.p0);
+        let r = a.p1 - a.p0;
+        let s = b.p1 - b.p0;
+        let origin = Point::origin();
+        let denominator = Point::cross(origin, r, s);
+        let t = Point::cross(origin, q - p, s);
+        let u = Point::cross(origin, q - p, r);
+        if denominator == 0 {
+            if t != 0 || u != 0 {
+                // parallel, or one segment is a single point off the line of the other
+                return Intersection::Disjoint;
             }
+            // collinear, where ordering by x then y orders points along the line
+            let start = cmp::max(cmp::min(a.p0, a.p1), cmp::min(b.p0, b.p1));
+            let end = cmp::min(cmp::max(a.p0, a.p1), cmp::max(b.p0, b.p1));
+            return match start.cmp(&end) {
+                cmp::Ordering::Less => Intersection::Overlap(Line::new(start, end)),
+                cmp::Ordering::Equal => {
+                    Intersection::Point(Rational::new(start.x, 1), Rational::new(start.y, 1))
+                }
+                cmp::Ordering::Greater => Intersection::Disjoint,
+            };
         }
-        None
+        // flip the signs so that t and u are in range when 0 <= t, u <= denominator
+        let sign = denominator.signum();
+        let (denominator, t, u) = (denominator * sign, t * sign, u * sign);
+        if !(0..=denominator).contains(&t) || !(0..=denominator).contains(&u) {
+            return Intersection::Disjoint;
+        }
+        Intersection::Point(
+            Rational::new((p.x * denominator) + (t * r.x), denominator),
+            Rational::new((p.y * denominator) + (t * r.y), denominator),
+        )
     }
 }
 
@@ -312,10 +747,43 @@ impl std::fmt::Display for Line {
     }
 }
 
+/// Maps a step off the edge of a grid, given the position it was taken from and its direction,
+/// to the position and direction it arrives with, or None if it cannot be taken
+pub type EdgeMap =
+    Arc<dyn Fn((usize, usize), Octile) -> Option<((usize, usize), Octile)> + Send + Sync>;
+
+/// How steps off the edge of a grid are treated
+#[derive(Clone, Default)]
+pub enum Topology {
+    /// Steps off the edge are not possible
+    #[default]
+    Bounded,
+    /// Steps off an edge wrap around to the opposite edge
+    Toroidal,
+    /// Steps off an edge are mapped by the given function, e.g. to fold the grid into a cube
+    #[allow(unused)]
+    Custom(EdgeMap),
+}
+
+/// Rotation or reflection of a grid, with rotations clockwise
+#[allow(unused)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Transform {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipHorizontal,
+    FlipVertical,
+    Transpose,
+}
+
+/// Two-dimensional grid indexed by (row, column), stored as one row-major vector
 pub struct Grid<T> {
     pub width: usize,
     pub height: usize,
-    inner: Vec<Vec<T>>,
+    inner: Vec<T>,
+    topology: Topology,
 }
 
 impl<T> Grid<T> {
@@ -323,115 +791,246 @@ impl<T> Grid<T> {
     where
         T: Clone + Default,
     {
-        let inner = vec![vec![T::default(); width]; height];
         Self {
             width,
             height,
-            inner,
+            inner: vec![T::default(); width * height],
+            topology: Topology::Bounded,
         }
     }
 
     pub fn distance(from: (usize, usize), to: (usize, usize)) -> usize {
         let (ai, aj) = from;
         let (bi, bj) = to;
-        let di = (bi as i64 - ai as i64).unsigned_abs();
-        let dj = (bj as i64 - aj as i64).unsigned_abs();
-        (di + dj) as usize
+        ai.abs_diff(bi) + aj.abs_diff(bj)
     }
 
-    pub fn get(&self, i: usize, j: usize) -> &T {
-        let row = self.inner.get(i).unwrap_or_else(|| {
+    fn index(&self, i: usize, j: usize, caller: &str) -> usize {
+        if i >= self.height || j >= self.width {
             panic!(
-                "Grid::get: index out of bounds: i: {}: size: {}x{}",
-                i, self.width, self.height
+                "Grid::{}: index out of bounds: ({}, {}): size: {}x{}",
+                caller, i, j, self.width, self.height
             )
-        });
-        row.get(j).unwrap_or_else(|| {
-            panic!(
-                "Grid::get: index out of bounds: j: {}: size: {}x{}",
-                j, self.width, self.height
-            )
-        })
+        }
+        i * self.width + j
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> &T {
+        &self.inner[self.index(i, j, "get")]
     }
 
     pub fn get_mut(&mut self, i: usize, j: usize) -> &mut T {
-        let row = self.inner.get_mut(i).unwrap_or_else(|| {
-            panic!(
-                "Grid::get_mut: index out of bounds: i: {}: size: {}x{}",
-                i, self.width, self.height
-            )
-        });
-        row.get_mut(j).unwrap_or_else(|| {
-            panic!(
-                "Grid::get_mut: index out of bounds: j: {}: size: {}x{}",
-                j, self.width, self.height
-            )
-        })
+        let index = self.index(i, j, "get_mut");
+        &mut self.inner[index]
     }
 
     pub fn set(&mut self, i: usize, j: usize, value: T) {
-        let row = self.inner.get_mut(i).unwrap_or_else(|| {
-            panic!(
-                "Grid::set: index out of bounds: i: {}: size: {}x{}",
-                i, self.width, self.height
-            )
-        });
-        let col = row.get_mut(j).unwrap_or_else(|| {
-            panic!(
-                "Grid::set: index out of bounds: j: {}: size: {}x{}",
-                j, self.width, self.height
-            )
-        });
-        *col = value;
+        let index = self.index(i, j, "set");
+        self.inner[index] = value;
     }
 
-    pub fn neighbor(&self, i: usize, j: usize, direction: Direction) -> Option<(usize, usize)> {
-        let (di, dj) = direction.grid_delta();
-        let point = Point::new_for_grid(i, j) + Point::new(di, dj);
-        if point.x >= 0
-            && point.y >= 0
-            && point.x < self.height as i64
-            && point.y < self.width as i64
-        {
-            Some((point.x as usize, point.y as usize))
+    pub fn contains(&self, i: usize, j: usize) -> bool {
+        i < self.height && j < self.width
+    }
+
+    #[allow(unused)]
+    pub fn try_get(&self, i: usize, j: usize) -> Option<&T> {
+        self.contains(i, j).then(|| &self.inner[i * self.width + j])
+    }
+
+    #[allow(unused)]
+    pub fn try_get_mut(&mut self, i: usize, j: usize) -> Option<&mut T> {
+        if self.contains(i, j) {
+            Some(&mut self.inner[i * self.width + j])
         } else {
             None
         }
     }
 
+    /// Sets how steps off the edge of the grid are treated by the movement helpers
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Position reached by moving (di, dj) from (i, j). The grid wraps around if it is toroidal;
+    /// otherwise the position must be within the grid, since an edge mapping only applies to
+    /// single steps.
+    pub fn offset(&self, i: usize, j: usize, di: i64, dj: i64) -> Option<(usize, usize)> {
+        if let Topology::Toroidal = self.topology {
+            if self.width == 0 || self.height == 0 {
+                return None;
+            }
+            let ii = (i as i64 + di).rem_euclid(self.height as i64) as usize;
+            let jj = (j as i64 + dj).rem_euclid(self.width as i64) as usize;
+            return Some((ii, jj));
+        }
+        let ii = i.checked_add_signed(di as isize)?;
+        let jj = j.checked_add_signed(dj as isize)?;
+        self.contains(ii, jj).then_some((ii, jj))
+    }
+
+    /// Position and direction after a single step from (i, j), following the grid topology
+    pub fn step(&self, i: usize, j: usize, direction: Octile) -> Option<((usize, usize), Octile)> {
+        let (di, dj) = direction.grid_delta();
+        match &self.topology {
+            Topology::Custom(edge_map) => match self.offset(i, j, di, dj) {
+                Some(position) => Some((position, direction)),
+                None => edge_map((i, j), direction).filter(|&((ii, jj), _)| self.contains(ii, jj)),
+            },
+            _ => self
+                .offset(i, j, di, dj)
+                .map(|position| (position, direction)),
+        }
+    }
+
+    #[allow(unused)]
+    pub fn get_offset(&self, i: usize, j: usize, di: i64, dj: i64) -> Option<&T> {
+        self.offset(i, j, di, dj).map(|(ii, jj)| self.get(ii, jj))
+    }
+
+    pub fn neighbor(&self, i: usize, j: usize, direction: Octile) -> Option<(usize, usize)> {
+        self.step(i, j, direction).map(|(position, _)| position)
+    }
+
     pub fn neighbors(&self, i: usize, j: usize) -> Vec<(usize, usize)> {
-        Direction::cardinal()
+        Octile::cardinal()
             .filter_map(|direction| self.neighbor(i, j, direction))
             .collect::<Vec<_>>()
     }
 
     pub fn neighbors_with_diagonal(&self, i: usize, j: usize) -> Vec<(usize, usize)> {
-        Direction::all_cases()
+        Octile::all_cases()
             .filter_map(|direction| self.neighbor(i, j, direction))
             .collect::<Vec<_>>()
     }
 
+    /// Distances from the nearest of the sources to every cell, where each step to a neighbor
+    /// costs 1 and is only taken if passable; unreachable cells have no distance
+    pub fn distances<I, P>(&self, sources: I, passable: P) -> Grid<Option<usize>>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+        P: Fn((usize, usize), (usize, usize)) -> bool,
+    {
+        let (width, passable) = (self.width, &passable);
+        let search = Search::new().starts(sources.into_iter().map(|(i, j)| i * width + j));
+        let distances = search.bfs_dense(self.inner.len(), |index| {
+            let (i, j) = (index / width, index % width);
+            self.neighbors(i, j)
+                .into_iter()
+                .filter(move |&next| passable((i, j), next))
+                .map(move |(ii, jj)| ii * width + jj)
+        });
+        self.distance_grid(distances)
+    }
+
+    /// Distances from the sources, each with an initial cost, to every cell, where the cost of
+    /// each step to a neighbor is given by step_cost, or None if the step cannot be taken
+    #[allow(unused)]
+    pub fn weighted_distances<I, F>(&self, sources: I, step_cost: F) -> Grid<Option<usize>>
+    where
+        I: IntoIterator<Item = ((usize, usize), usize)>,
+        F: Fn((usize, usize), (usize, usize)) -> Option<usize>,
+    {
+        let distances = self.weighted_search(self.weighted_sources(sources), step_cost);
+        self.distance_grid(distances)
+    }
+
+    /// Distance from the sources to the target as for weighted_distances, stopping as soon as the
+    /// target is reached
+    pub fn weighted_distance<I, F>(
+        &self,
+        sources: I,
+        (i, j): (usize, usize),
+        step_cost: F,
+    ) -> Option<usize>
+    where
+        I: IntoIterator<Item = ((usize, usize), usize)>,
+        F: Fn((usize, usize), (usize, usize)) -> Option<usize>,
+    {
+        let target = i * self.width + j;
+        let search = self
+            .weighted_sources(sources)
+            .goal(move |&index| index == target);
+        self.weighted_search(search, step_cost)[target]
+    }
+
+    // cells are numbered in row-major order, as they are stored
+    fn weighted_sources<'a, I>(&self, sources: I) -> Search<'a, usize, usize>
+    where
+        I: IntoIterator<Item = ((usize, usize), usize)>,
+    {
+        sources
+            .into_iter()
+            .fold(Search::new(), |search, ((i, j), cost)| {
+                search.start_with_cost(i * self.width + j, cost)
+            })
+    }
+
+    fn weighted_search<F>(&self, search: Search<usize, usize>, step_cost: F) -> Vec<Option<usize>>
+    where
+        F: Fn((usize, usize), (usize, usize)) -> Option<usize>,
+    {
+        let (width, step_cost) = (self.width, &step_cost);
+        search.run_dense(self.inner.len(), |index| {
+            let (i, j) = (index / width, index % width);
+            self.neighbors(i, j)
+                .into_iter()
+                .filter_map(move |(ii, jj)| Some((ii * width + jj, step_cost((i, j), (ii, jj))?)))
+        })
+    }
+
+    fn distance_grid(&self, distances: Vec<Option<usize>>) -> Grid<Option<usize>> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            inner: distances,
+            topology: Topology::Bounded,
+        }
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.inner[(i * self.width)..((i + 1) * self.width)]
+    }
+
+    #[allow(unused)]
+    pub fn row_mut(&mut self, i: usize) -> &mut [T] {
+        &mut self.inner[(i * self.width)..((i + 1) * self.width)]
+    }
+
+    #[allow(unused)]
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.inner.chunks(self.width.max(1)).take(self.height)
+    }
+
     pub fn iter_row(&self, i: usize) -> impl Iterator<Item = &T> {
-        self.inner[i].iter()
+        self.row(i).iter()
     }
 
     pub fn iter_col(&self, j: usize) -> impl Iterator<Item = &T> {
-        (0..self.height).map(move |i| &self.inner[i][j])
+        if j >= self.width {
+            panic!(
+                "Grid::iter_col: column out of bounds: {}: size: {}x{}",
+                j, self.width, self.height
+            )
+        }
+        self.inner[j..].iter().step_by(self.width).take(self.height)
     }
 
     pub fn iter_grid(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        let width = self.width;
         self.inner
             .iter()
             .enumerate()
-            .flat_map(|(i, row)| row.iter().enumerate().map(move |(j, item)| (i, j, item)))
+            .map(move |(index, item)| (index / width, index % width, item))
     }
 
     pub fn iter_grid_mut(&mut self) -> impl Iterator<Item = (usize, usize, &mut T)> {
-        self.inner.iter_mut().enumerate().flat_map(|(i, row)| {
-            row.iter_mut()
-                .enumerate()
-                .map(move |(j, item)| (i, j, item))
-        })
+        let width = self.width;
+        self.inner
+            .iter_mut()
+            .enumerate()
+            .map(move |(index, item)| (index / width, index % width, item))
     }
 
     pub fn find(&self, element: &T) -> Option<(usize, usize)>
@@ -483,306 +1082,1512 @@ impl<T> Grid<T> {
         }
         indices
     }
-}
 
-impl<T> Clone for Grid<T>
-where
-    T: Clone,
-{
-    fn clone(&self) -> Self {
-        Self {
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: Fn(&T) -> U,
+    {
+        Grid {
             width: self.width,
             height: self.height,
-            inner: self.inner.clone(),
+            inner: self.inner.iter().map(f).collect(),
+            topology: self.topology.clone(),
         }
     }
-}
 
-impl<I, T> FromIterator<I> for Grid<T>
-where
-    I: Iterator<Item = T>,
-    T: Clone,
-{
-    fn from_iter<J>(iter: J) -> Self
+    /// Builds a grid by calling the given function with the position of each cell
+    pub fn from_fn<F>(width: usize, height: usize, mut f: F) -> Self
     where
-        J: IntoIterator<Item = I>,
+        F: FnMut(usize, usize) -> T,
     {
-        let inner = iter
-            .into_iter()
-            .map(|inner| inner.collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-        let height = inner.len();
-        let width = inner[0].len();
+        let mut inner = Vec::with_capacity(width * height);
+        for i in 0..height {
+            for j in 0..width {
+                inner.push(f(i, j));
+            }
+        }
         Self {
             width,
             height,
             inner,
+            topology: Topology::Bounded,
         }
     }
-}
 
-impl<T> From<Vec<Vec<T>>> for Grid<T>
-where
-    T: Clone,
-{
-    fn from(value: Vec<Vec<T>>) -> Self {
-        let height = value.len();
-        let width = value[0].len();
-        Self {
-            width,
-            height,
-            inner: value,
-        }
+    #[allow(unused)]
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.height, self.width, |i, j| self.get(j, i).clone())
     }
-}
 
-impl From<String> for Grid<char> {
-    fn from(value: String) -> Self {
-        value.split('\n').map(|line| line.chars()).collect()
+    #[allow(unused)]
+    pub fn rotate_90_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.height, self.width, |i, j| {
+            self.get(self.height - 1 - j, i).clone()
+        })
     }
-}
 
-impl std::fmt::Display for Grid<char> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (i, j, c) in self.iter_grid() {
-            if j == 0 && i > 0 {
-                writeln!(f)?;
-            }
-            write!(f, "{}", c)?;
-        }
-        writeln!(f)
+    #[allow(unused)]
+    pub fn rotate_180(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.width, self.height, |i, j| {
+            self.get(self.height - 1 - i, self.width - 1 - j).clone()
+        })
     }
-}
-
-#[derive(Clone, Debug)]
-pub struct RangeInclusive<T>
-where
-    T: Clone + Copy + Ord + PartialOrd + Sub<Output = T>,
-{
-    pub start: T,
-    pub end: T,
-}
 
-impl<T> RangeInclusive<T>
-where
-    T: Clone + Copy + Ord + PartialOrd + Sub<Output = T>,
-{
-    pub fn new(start: T, end: T) -> Self {
-        Self { start, end }
+    #[allow(unused)]
+    pub fn rotate_90_counterclockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.height, self.width, |i, j| {
+            self.get(j, self.width - 1 - i).clone()
+        })
     }
 
-    pub fn contains(&self, value: &T) -> bool {
-        value >= &self.start && value <= &self.end
+    /// Mirrors the grid left to right
+    #[allow(unused)]
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.width, self.height, |i, j| {
+            self.get(i, self.width - 1 - j).clone()
+        })
     }
 
-    pub fn size(&self) -> T {
-        self.end - self.start
+    /// Mirrors the grid top to bottom
+    #[allow(unused)]
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.width, self.height, |i, j| {
+            self.get(self.height - 1 - i, j).clone()
+        })
     }
 
-    pub fn overlaps(&self, other: &Self) -> bool {
-        (other.start >= self.start && other.start <= self.end)
-            || (other.end >= self.start && other.end <= self.end)
+    /// Surrounds the grid with a border of the given thickness
+    #[allow(unused)]
+    pub fn pad(&self, amount: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(
+            self.width + 2 * amount,
+            self.height + 2 * amount,
+            |i, j| match (i.checked_sub(amount), j.checked_sub(amount)) {
+                (Some(ii), Some(jj)) if ii < self.height && jj < self.width => {
+                    self.get(ii, jj).clone()
+                }
+                _ => value.clone(),
+            },
+        )
+    }
+
+    /// View of the rectangle with its top-left corner at (i, j), clipped to the grid
+    pub fn sub_grid(&self, i: usize, j: usize, width: usize, height: usize) -> SubGrid<'_, T> {
+        let i = i.min(self.height);
+        let j = j.min(self.width);
+        SubGrid {
+            grid: self,
+            i,
+            j,
+            width: width.min(self.width - j),
+            height: height.min(self.height - i),
+        }
     }
 
-    fn try_combine(&self, other: &Self) -> (Self, Option<Self>) {
-        if self.overlaps(other) {
-            let min = cmp::min(self.start, other.start);
-            let max = cmp::max(self.end, other.end);
-            (Self::new(min, max), None)
-        } else {
-            (self.clone(), Some(other.clone()))
+    /// Rotates and flips the grid as described by the transform
+    #[allow(unused)]
+    pub fn transform(&self, transform: Transform) -> Self
+    where
+        T: Clone,
+    {
+        match transform {
+            Transform::Identity => self.clone(),
+            Transform::Rotate90 => self.rotate_90_clockwise(),
+            Transform::Rotate180 => self.rotate_180(),
+            Transform::Rotate270 => self.rotate_90_counterclockwise(),
+            Transform::FlipHorizontal => self.flip_horizontal(),
+            Transform::FlipVertical => self.flip_vertical(),
+            Transform::Transpose => self.transpose(),
         }
     }
 
-    fn reduce_recursive(input: Vec<Self>, current: Self, index: usize, output: &mut Vec<Self>) {
-        match &input[index..] {
-            [] => {
-                output.push(current);
-            }
-            [next, ..] => {
-                let combination = current.try_combine(next);
-                if let (range_a, Some(range_b)) = combination {
-                    // Unsuccessful combination
-                    output.push(range_a);
-                    Self::reduce_recursive(input, range_b, index + 1, output)
-                } else if let (range_a, None) = combination {
-                    // Successful combination
-                    Self::reduce_recursive(input, range_a, index + 1, output)
-                } else {
-                    unreachable!()
-                }
-            }
+    /// Repeats the grid as a rows x cols arrangement of tiles, where each tile is the grid
+    /// transformed as given for its position. Every tile must end up the same size, so a grid
+    /// which is not square cannot be turned by 90 degrees in some tiles and not others.
+    #[allow(unused)]
+    pub fn tile<F>(&self, rows: usize, cols: usize, f: F) -> Self
+    where
+        T: Clone,
+        F: Fn(usize, usize) -> Transform,
+    {
+        let tiles = (0..rows)
+            .map(|row| (0..cols).map(|col| self.transform(f(row, col))).collect())
+            .collect::<Vec<Vec<_>>>();
+        let (width, height) = tiles
+            .first()
+            .and_then(|row| row.first())
+            .map_or((0, 0), |tile| (tile.width, tile.height));
+        if tiles
+            .iter()
+            .flatten()
+            .any(|tile| (tile.width, tile.height) != (width, height))
+        {
+            panic!("Grid::tile: transformed tiles differ in size");
         }
+        Self::from_fn(width * cols, height * rows, |i, j| {
+            tiles[i / height][j / width]
+                .get(i % height, j % width)
+                .clone()
+        })
     }
 
-    pub fn reduce(mut ranges: Vec<Self>) -> Vec<Self> {
-        let mut output = Vec::with_capacity(ranges.len());
-        // Sort the ranges to start
-        ranges.sort_by(|a, b| a.start.cmp(&b.start));
-        if !ranges.is_empty() {
-            let first = ranges[0].clone();
-            Self::reduce_recursive(ranges, first, 1, &mut output);
+    /// Replaces each cell with N cells side by side, so the grid becomes N times as wide
+    pub fn widen<U, F, const N: usize>(&self, f: F) -> Grid<U>
+    where
+        F: Fn(&T) -> [U; N],
+    {
+        Grid {
+            width: self.width * N,
+            height: self.height,
+            inner: self.inner.iter().flat_map(f).collect(),
+            topology: Topology::Bounded,
         }
-        output
     }
-}
 
-pub type Maze = Grid<char>;
+    /// Positions from (i, j) moving down and to the right
+    fn diagonal_positions(&self, i: usize, j: usize) -> impl Iterator<Item = (usize, usize)> {
+        let length = self
+            .height
+            .saturating_sub(i)
+            .min(self.width.saturating_sub(j));
+        (0..length).map(move |n| (i + n, j + n))
+    }
 
-impl Maze {
-    pub fn create(width: usize, height: usize) -> Self {
-        let inner = vec![vec!['.'; width]; height];
-        Self {
-            width,
-            height,
-            inner,
-        }
+    /// Positions from (i, j) moving down and to the left
+    fn antidiagonal_positions(&self, i: usize, j: usize) -> impl Iterator<Item = (usize, usize)> {
+        let length = self.height.saturating_sub(i).min(j + 1);
+        (0..length).map(move |n| (i + n, j - n))
     }
 
-    pub fn is_wall(&self, i: usize, j: usize) -> bool {
-        self.get(i, j) == &'#'
+    /// Cells from (i, j) moving down and to the right
+    #[allow(unused)]
+    pub fn iter_diagonal(&self, i: usize, j: usize) -> impl Iterator<Item = &T> {
+        self.diagonal_positions(i, j)
+            .map(|position| &self[position])
     }
 
-    pub fn distance_matrix(&self, start: (usize, usize)) -> Grid<usize> {
-        let mut distances = Grid::from(vec![vec![usize::MAX; self.width]; self.height]);
-        let mut heap = BinaryHeap::new();
+    /// Cells from (i, j) moving down and to the left
+    #[allow(unused)]
+    pub fn iter_antidiagonal(&self, i: usize, j: usize) -> impl Iterator<Item = &T> {
+        self.antidiagonal_positions(i, j)
+            .map(|position| &self[position])
+    }
+
+    /// Positions along every diagonal running down and to the right, starting from the
+    /// bottom-left corner
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = (usize, usize)>> {
+        let starts = (0..self.height)
+            .rev()
+            .map(|i| (i, 0))
+            .chain((1..self.width).map(|j| (0, j)));
+        starts.map(|(i, j)| self.diagonal_positions(i, j))
+    }
+
+    /// Positions along every diagonal running down and to the left, starting from the top-left
+    /// corner
+    pub fn antidiagonals(&self) -> impl Iterator<Item = impl Iterator<Item = (usize, usize)>> {
+        let last = self.width.saturating_sub(1);
+        let starts = (0..self.width)
+            .map(|j| (0, j))
+            .chain((1..self.height).map(move |i| (i, last)));
+        starts.map(|(i, j)| self.antidiagonal_positions(i, j))
+    }
+}
 
-        distances.set(start.0, start.1, 0);
-        heap.push(SearchState {
-            position: start,
-            cost: 0,
-        });
+/// Borrowed rectangular region of a grid, indexed relative to its top-left corner
+pub struct SubGrid<'a, T> {
+    grid: &'a Grid<T>,
+    i: usize,
+    j: usize,
+    pub width: usize,
+    pub height: usize,
+}
 
-        while let Some(SearchState {
-            position: (i, j),
-            cost,
-        }) = heap.pop()
-        {
-            let distance = distances.get(i, j);
-            if *distance < cost {
-                continue;
-            }
-            for (ii, jj) in self.neighbors(i, j) {
-                if self.is_wall(ii, jj) {
-                    continue;
-                }
-                let next_distance = distances.get_mut(ii, jj);
-                if *next_distance > cost + 1 {
-                    *next_distance = cost + 1;
-                    heap.push(SearchState {
-                        position: (ii, jj),
-                        cost: cost + 1,
-                    });
-                }
-            }
+impl<T> SubGrid<'_, T> {
+    pub fn get(&self, i: usize, j: usize) -> &T {
+        if i >= self.height || j >= self.width {
+            panic!(
+                "SubGrid::get: index out of bounds: ({}, {}): size: {}x{}",
+                i, j, self.width, self.height
+            )
         }
-        distances
+        self.grid.get(self.i + i, self.j + j)
     }
 
     #[allow(unused)]
-    pub fn to_csv(&self) -> String {
-        let mut rows = Vec::with_capacity(self.height);
-        for i in 0..self.height {
-            let row = self
-                .iter_row(i)
-                .map(|c| c.to_string())
-                .collect::<Vec<_>>()
-                .join(",");
-            rows.push(row);
-        }
-        rows.join("\n")
+    pub fn iter_grid(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        (0..self.height).flat_map(move |i| (0..self.width).map(move |j| (i, j, self.get(i, j))))
     }
-}
-
-#[derive(Clone, Eq, PartialEq)]
-pub struct SearchState {
-    position: (usize, usize),
-    cost: usize,
-}
 
-impl Ord for SearchState {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| self.position.cmp(&other.position))
+    #[allow(unused)]
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.width, self.height, |i, j| self.get(i, j).clone())
     }
 }
 
-impl PartialOrd for SearchState {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &Self::Output {
+        self.get(i, j)
     }
 }
 
-pub struct Counter<T>
-where
-    T: Hash + Eq,
-{
-    counts: HashMap<T, usize>,
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Self::Output {
+        self.get_mut(i, j)
+    }
 }
 
-impl<T> Counter<T>
+impl<T> Clone for Grid<T>
 where
-    T: Hash + Eq,
+    T: Clone,
 {
-    pub fn new() -> Self {
+    fn clone(&self) -> Self {
         Self {
-            counts: HashMap::new(),
+            width: self.width,
+            height: self.height,
+            inner: self.inner.clone(),
+            topology: self.topology.clone(),
         }
     }
+}
 
-    pub fn get(&self, element: T) -> usize {
-        *self.counts.get(&element).unwrap_or(&0)
+/// Grids are equal if their cells are, whatever their topology
+impl<T> PartialEq for Grid<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width && self.height == other.height && self.inner == other.inner
     }
+}
 
-    pub fn add(&mut self, element: T) {
-        let entry = self.counts.entry(element).or_insert(0);
-        *entry += 1;
-    }
+impl<T> Eq for Grid<T> where T: Eq {}
 
-    pub fn add_many(&mut self, element: T, count: usize) {
-        let entry = self.counts.entry(element).or_insert(0);
-        *entry += count;
+impl<T> Hash for Grid<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width.hash(state);
+        self.height.hash(state);
+        self.inner.hash(state);
     }
+}
 
-    pub fn extend<I>(&mut self, iter: I)
+impl<I, T> FromIterator<I> for Grid<T>
+where
+    I: Iterator<Item = T>,
+    T: Clone,
+{
+    fn from_iter<J>(iter: J) -> Self
     where
-        I: Iterator<Item = T>,
+        J: IntoIterator<Item = I>,
     {
-        for item in iter {
-            self.add(item);
-        }
-    }
-
-    pub fn remove(&mut self, element: T) -> Option<usize> {
-        self.counts.remove(&element)
-    }
-
-    pub fn sorted(&self) -> impl Iterator<Item = (&T, usize)> {
-        let mut paired_list = self.counts.iter().map(|(k, &v)| (k, v)).collect::<Vec<_>>();
-        paired_list.sort_by_key(|&(_, v)| v);
-        paired_list.into_iter().rev()
+        let rows = iter
+            .into_iter()
+            .map(|inner| inner.collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        Self::from(rows)
     }
+}
 
-    pub fn min(&self) -> usize {
-        let sorted = self.sorted().collect::<Vec<_>>();
-        sorted.last().map_or(0, |&(_, count)| count)
+impl<T> From<Vec<Vec<T>>> for Grid<T>
+where
+    T: Clone,
+{
+    fn from(value: Vec<Vec<T>>) -> Self {
+        let height = value.len();
+        let width = value.first().map_or(0, |row| row.len());
+        let mut inner = Vec::with_capacity(width * height);
+        for row in value {
+            assert_eq!(row.len(), width, "Grid::from: rows have different lengths");
+            inner.extend(row);
+        }
+        Self {
+            width,
+            height,
+            inner,
+            topology: Topology::Bounded,
+        }
+    }
+}
+
+impl From<String> for Grid<char> {
+    fn from(value: String) -> Self {
+        value
+            .parse()
+            .unwrap_or_else(|err| panic!("Grid::from: {}", err))
+    }
+}
+
+/// Reasons a grid could not be parsed from text
+#[derive(Debug, PartialEq)]
+pub enum GridParseError {
+    Empty,
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    BadCell {
+        row: usize,
+        col: usize,
+        cell: char,
+    },
+}
+
+impl std::fmt::Display for GridParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty grid"),
+            Self::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(f, "row {} has {} cells, expected {}", row, found, expected),
+            Self::BadCell { row, col, cell } => {
+                write!(f, "invalid cell {:?} at ({}, {})", cell, row, col)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GridParseError {}
+
+/// Positions of each marker character found while parsing a grid
+pub type MarkerPositions = HashMap<char, Vec<(usize, usize)>>;
+
+impl<T> Grid<T> {
+    fn parse_cells<F>(input: &str, mut f: F) -> Result<Self, GridParseError>
+    where
+        F: FnMut((usize, usize), char) -> Option<T>,
+    {
+        let lines = input
+            .trim_end_matches(['\r', '\n'])
+            .lines()
+            .collect::<Vec<_>>();
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        if width == 0 {
+            return Err(GridParseError::Empty);
+        }
+
+        let mut inner = Vec::with_capacity(width * lines.len());
+        for (i, line) in lines.iter().enumerate() {
+            let found = line.chars().count();
+            if found != width {
+                return Err(GridParseError::RaggedRow {
+                    row: i,
+                    expected: width,
+                    found,
+                });
+            }
+            for (j, c) in line.chars().enumerate() {
+                let cell = f((i, j), c).ok_or(GridParseError::BadCell {
+                    row: i,
+                    col: j,
+                    cell: c,
+                })?;
+                inner.push(cell);
+            }
+        }
+        Ok(Self {
+            width,
+            height: lines.len(),
+            inner,
+            topology: Topology::Bounded,
+        })
+    }
+
+    /// Parses one row per line, converting each character with the given function. Windows line
+    /// endings and trailing newlines are accepted.
+    pub fn parse_with<F>(input: &str, f: F) -> Result<Self, GridParseError>
+    where
+        F: Fn(char) -> Option<T>,
+    {
+        Self::parse_cells(input, |_, c| f(c))
+    }
+
+    /// Parses a grid where marker characters, such as start and end positions, appear in place
+    /// of cells. Each marker is parsed as the replacement character, and the positions of each
+    /// marker are returned in reading order.
+    pub fn parse_with_markers(
+        input: &str,
+        markers: &[char],
+        replacement: char,
+    ) -> Result<(Self, MarkerPositions), GridParseError>
+    where
+        T: TryFrom<char>,
+    {
+        let mut positions = markers
+            .iter()
+            .map(|&marker| (marker, Vec::new()))
+            .collect::<HashMap<_, _>>();
+        let grid = Self::parse_cells(input, |position, c| match positions.get_mut(&c) {
+            Some(found) => {
+                found.push(position);
+                T::try_from(replacement).ok()
+            }
+            None => T::try_from(c).ok(),
+        })?;
+        Ok((grid, positions))
+    }
+}
+
+impl<T> std::str::FromStr for Grid<T>
+where
+    T: TryFrom<char>,
+{
+    type Err = GridParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, |c| T::try_from(c).ok())
+    }
+}
+
+impl<T> TryFrom<&str> for Grid<T>
+where
+    T: TryFrom<char>,
+{
+    type Error = GridParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Which neighbors of a cell are connected to it
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Connectivity {
+    /// Only the cardinal neighbors
+    Four,
+    /// The cardinal and diagonal neighbors
+    #[allow(unused)]
+    Eight,
+}
+
+impl Connectivity {
+    fn directions(self) -> &'static [Octile] {
+        match self {
+            Self::Four => &[Octile::North, Octile::South, Octile::East, Octile::West],
+            Self::Eight => &[
+                Octile::North,
+                Octile::South,
+                Octile::East,
+                Octile::West,
+                Octile::NorthEast,
+                Octile::NorthWest,
+                Octile::SouthEast,
+                Octile::SouthWest,
+            ],
+        }
+    }
+}
+
+/// Measurements of one connected region of a grid
+#[derive(Clone, Debug, PartialEq)]
+pub struct Region {
+    /// A cell of the region, from which it was labeled
+    pub start: (usize, usize),
+    pub area: usize,
+    /// Number of cell edges between the region and other regions or the outside of the grid
+    pub perimeter: usize,
+    /// Number of straight sides of the region's boundary, including the boundaries of any holes
+    pub sides: usize,
+    /// Top-left and bottom-right corners of the bounding box, inclusive
+    pub bounds: ((usize, usize), (usize, usize)),
+}
+
+/// Connected regions of a grid, where each cell is labeled with the index of its region
+pub struct Regions {
+    pub labels: Grid<usize>,
+    pub regions: Vec<Region>,
+}
+
+impl<T> Grid<T> {
+    fn connected(
+        &self,
+        i: usize,
+        j: usize,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        connectivity
+            .directions()
+            .iter()
+            .filter_map(move |&direction| self.neighbor(i, j, direction))
+    }
+
+    /// Cells reachable from the seeds, where a step is taken from one cell to a connected
+    /// neighbor if same_region holds for their values; cells are returned in the order reached
+    #[allow(unused)]
+    pub fn flood_fill<I, F>(
+        &self,
+        seeds: I,
+        connectivity: Connectivity,
+        same_region: F,
+    ) -> Vec<(usize, usize)>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+        F: Fn(&T, &T) -> bool,
+    {
+        let mut filled = Grid::<bool>::new(self.width, self.height);
+        self.fill(seeds, connectivity, &same_region, &mut filled)
+    }
+
+    // flood fill which skips cells already marked as filled, so that several fills can share
+    // one grid of marks rather than each allocating their own
+    fn fill<I, F>(
+        &self,
+        seeds: I,
+        connectivity: Connectivity,
+        same_region: &F,
+        filled: &mut Grid<bool>,
+    ) -> Vec<(usize, usize)>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+        F: Fn(&T, &T) -> bool,
+    {
+        let mut cells = Vec::new();
+        let mut queue = VecDeque::new();
+        for (i, j) in seeds {
+            if !filled.get(i, j) {
+                filled.set(i, j, true);
+                queue.push_back((i, j));
+            }
+        }
+        while let Some((i, j)) = queue.pop_front() {
+            cells.push((i, j));
+            for (ii, jj) in self.connected(i, j, connectivity) {
+                if !filled.get(ii, jj) && same_region(self.get(i, j), self.get(ii, jj)) {
+                    filled.set(ii, jj, true);
+                    queue.push_back((ii, jj));
+                }
+            }
+        }
+        cells
+    }
+
+    /// Splits the whole grid into connected regions, where neighboring cells belong to the same
+    /// region if same_region holds for their values
+    pub fn label_regions<F>(&self, connectivity: Connectivity, same_region: F) -> Regions
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        let mut labels = Grid::from_fn(self.width, self.height, |_, _| usize::MAX)
+            .with_topology(self.topology.clone());
+        let mut filled = Grid::<bool>::new(self.width, self.height);
+        let mut regions = Vec::new();
+        for i in 0..self.height {
+            for j in 0..self.width {
+                if *filled.get(i, j) {
+                    continue;
+                }
+                let label = regions.len();
+                let cells = self.fill([(i, j)], connectivity, &same_region, &mut filled);
+                let mut bounds = ((i, j), (i, j));
+                for &(ii, jj) in &cells {
+                    labels.set(ii, jj, label);
+                    bounds.0 = (cmp::min(bounds.0.0, ii), cmp::min(bounds.0.1, jj));
+                    bounds.1 = (cmp::max(bounds.1.0, ii), cmp::max(bounds.1.1, jj));
+                }
+                regions.push(Region {
+                    start: (i, j),
+                    area: cells.len(),
+                    perimeter: 0,
+                    sides: 0,
+                    bounds,
+                });
+            }
+        }
+
+        // a side starts at every corner of the boundary, so sides are counted by their corners
+        let corners = [
+            (Octile::North, Octile::East, Octile::NorthEast),
+            (Octile::East, Octile::South, Octile::SouthEast),
+            (Octile::South, Octile::West, Octile::SouthWest),
+            (Octile::West, Octile::North, Octile::NorthWest),
+        ];
+        for (i, j, &label) in labels.iter_grid() {
+            let inside = |direction: Octile| {
+                labels
+                    .neighbor(i, j, direction)
+                    .is_some_and(|(ii, jj)| *labels.get(ii, jj) == label)
+            };
+            let region = &mut regions[label];
+            region.perimeter += Octile::cardinal()
+                .filter(|&direction| !inside(direction))
+                .count();
+            region.sides += corners
+                .iter()
+                .filter(|&&(a, b, diagonal)| {
+                    // exterior corners turn away from both sides, interior corners only from the
+                    // diagonal between them
+                    (!inside(a) && !inside(b)) || (inside(a) && inside(b) && !inside(diagonal))
+                })
+                .count();
+        }
+
+        Regions { labels, regions }
+    }
+}
+
+impl std::fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, j, c) in self.iter_grid() {
+            if j == 0 && i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", c)?;
+        }
+        writeln!(f)
+    }
+}
+
+/// Set of values stored as sorted, disjoint and non-adjacent half-open intervals
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Range<T>>,
+}
+
+impl<T> IntervalSet<T>
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T> + Default,
+{
+    #[allow(unused)]
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Total number of values in the set
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::default(), |len, range| len + (range.end - range.start))
+    }
+
+    #[allow(unused)]
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Intervals of the set in ascending order
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Range<T>> + '_ {
+        self.intervals.iter().cloned()
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let index = self.intervals.partition_point(|range| range.end <= *value);
+        self.intervals
+            .get(index)
+            .is_some_and(|range| range.start <= *value)
+    }
+
+    /// Adds every value in the range, merging it with any intervals it overlaps or touches
+    #[allow(unused)]
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.intervals.partition_point(|r| r.end < range.start);
+        let last = self.intervals.partition_point(|r| r.start <= range.end);
+        let merged = if first < last {
+            cmp::min(range.start, self.intervals[first].start)
+                ..cmp::max(range.end, self.intervals[last - 1].end)
+        } else {
+            range
+        };
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// Removes every value in the range, splitting any interval it falls within
+    #[allow(unused)]
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.intervals.partition_point(|r| r.end <= range.start);
+        let last = self.intervals.partition_point(|r| r.start < range.end);
+        if first >= last {
+            return;
+        }
+        let before = self.intervals[first].start..range.start;
+        let after = range.end..self.intervals[last - 1].end;
+        let remaining = [before, after].into_iter().filter(|r| !r.is_empty());
+        self.intervals.splice(first..last, remaining);
+    }
+
+    #[allow(unused)]
+    pub fn union(&self, other: &Self) -> Self {
+        self.iter().chain(other.iter()).collect()
+    }
+
+    #[allow(unused)]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut a, mut b) = (
+            self.intervals.iter().peekable(),
+            other.intervals.iter().peekable(),
+        );
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let overlap = cmp::max(x.start, y.start)..cmp::min(x.end, y.end);
+            if !overlap.is_empty() {
+                intervals.push(overlap);
+            }
+            // drop whichever interval ends first, as it cannot overlap anything further
+            if x.end <= y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { intervals }
+    }
+
+    #[allow(unused)]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let mut others = other.intervals.iter().peekable();
+        for range in self.intervals.iter() {
+            let mut start = range.start;
+            // skip intervals which end before this one starts
+            while others.next_if(|r| r.end <= start).is_some() {}
+            while let Some(r) = others.peek()
+                && r.start < range.end
+            {
+                if start < r.start {
+                    intervals.push(start..r.start);
+                }
+                start = cmp::max(start, r.end);
+                if r.end > range.end {
+                    break;
+                }
+                others.next();
+            }
+            if start < range.end {
+                intervals.push(start..range.end);
+            }
+        }
+        Self { intervals }
+    }
+}
+
+impl<T> FromIterator<Range<T>> for IntervalSet<T>
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T> + Default,
+{
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut ranges = iter
+            .into_iter()
+            .filter(|range| !range.is_empty())
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|range| range.start);
+        // merge each range into the last interval where they overlap or touch
+        let mut intervals = Vec::<Range<T>>::with_capacity(ranges.len());
+        for range in ranges {
+            match intervals.last_mut() {
+                Some(last) if range.start <= last.end => last.end = cmp::max(last.end, range.end),
+                _ => intervals.push(range),
+            }
+        }
+        Self { intervals }
+    }
+}
+
+/// Piecewise mapping of values by adding an offset to those in each of a set of disjoint
+/// intervals, and leaving values outside of the intervals unchanged. Offsets have the same type
+/// as the values, so a map over an unsigned type can only shift values upward; use a signed type
+/// for maps which shift values down.
+#[derive(Clone, Debug, Default)]
+pub struct IntervalMap<T> {
+    entries: Vec<(Range<T>, T)>,
+}
+
+impl<T> IntervalMap<T>
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T> + Default,
+{
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Maps values in the range by the offset. Panics if it overlaps an existing range.
+    pub fn insert(&mut self, range: Range<T>, offset: T) {
+        let index = self.entries.partition_point(|(r, _)| r.start < range.start);
+        let overlaps_before = index > 0 && self.entries[index - 1].0.end > range.start;
+        let overlaps_after = self
+            .entries
+            .get(index)
+            .is_some_and(|(r, _)| r.start < range.end);
+        if overlaps_before || overlaps_after {
+            panic!("IntervalMap::insert: range overlaps an existing range");
+        }
+        self.entries.insert(index, (range, offset));
+    }
+
+    pub fn get(&self, value: T) -> T {
+        let index = self.entries.partition_point(|(r, _)| r.end <= value);
+        match self.entries.get(index) {
+            Some((range, offset)) if range.start <= value => value + *offset,
+            _ => value,
+        }
+    }
+
+    /// Maps every value in the set, splitting its intervals wherever the offset changes
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut mapped = Vec::new();
+        for range in set.iter() {
+            let mut start = range.start;
+            let first = self.entries.partition_point(|(r, _)| r.end <= start);
+            for (r, offset) in self.entries[first..].iter() {
+                if r.start >= range.end {
+                    break;
+                }
+                // values before this entry are left as they are
+                if start < r.start {
+                    mapped.push(start..r.start);
+                    start = r.start;
+                }
+                let end = cmp::min(range.end, r.end);
+                mapped.push((start + *offset)..(end + *offset));
+                start = end;
+            }
+            if start < range.end {
+                mapped.push(start..range.end);
+            }
+        }
+        mapped.into_iter().collect()
+    }
+}
+
+pub type Maze = Grid<char>;
+
+impl Maze {
+    pub fn create(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            inner: vec!['.'; width * height],
+            topology: Topology::Bounded,
+        }
+    }
+
+    pub fn is_wall(&self, i: usize, j: usize) -> bool {
+        self.get(i, j) == &'#'
+    }
+
+    pub fn distance_matrix(&self, start: (usize, usize)) -> Grid<Option<usize>> {
+        self.distances([start], |_, (i, j)| !self.is_wall(i, j))
+    }
+
+    #[allow(unused)]
+    pub fn to_csv(&self) -> String {
+        let mut rows = Vec::with_capacity(self.height);
+        for i in 0..self.height {
+            let row = self
+                .iter_row(i)
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            rows.push(row);
+        }
+        rows.join("\n")
+    }
+}
+
+/// Unbounded grid keyed by point, where x is the column and y is the row; positions which have
+/// not been set hold the default value
+#[derive(Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    default: T,
+    // corners of the bounding box of all set positions
+    bounds: Option<(Point, Point)>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        Self {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    /// Converts a dense grid, storing only the cells which differ from the default value
+    #[allow(unused)]
+    pub fn from_grid(grid: &Grid<T>, default: T) -> Self
+    where
+        T: Clone + PartialEq,
+    {
+        let mut sparse = Self::new(default);
+        for (i, j, cell) in grid.iter_grid() {
+            if *cell != sparse.default {
+                sparse.set(Point::new(j as i64, i as i64), cell.clone());
+            }
+        }
+        sparse
+    }
+
+    pub fn get(&self, point: &Point) -> &T {
+        self.cells.get(point).unwrap_or(&self.default)
+    }
+
+    pub fn set(&mut self, point: Point, value: T) {
+        self.bounds = Some(match self.bounds {
+            None => (point, point),
+            Some((min, max)) => (
+                Point::new(min.x.min(point.x), min.y.min(point.y)),
+                Point::new(max.x.max(point.x), max.y.max(point.y)),
+            ),
+        });
+        self.cells.insert(point, value);
+    }
+
+    #[allow(unused)]
+    pub fn remove(&mut self, point: &Point) -> Option<T> {
+        let value = self.cells.remove(point)?;
+        // the bounding box only shrinks if the point was on its edge
+        if let Some((min, max)) = self.bounds
+            && (point.x == min.x || point.x == max.x || point.y == min.y || point.y == max.y)
+        {
+            self.bounds = self.cells.keys().fold(None, |bounds, p| match bounds {
+                None => Some((*p, *p)),
+                Some((min, max)) => Some((
+                    Point::new(min.x.min(p.x), min.y.min(p.y)),
+                    Point::new(max.x.max(p.x), max.y.max(p.y)),
+                )),
+            });
+        }
+        Some(value)
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.cells.contains_key(point)
+    }
+
+    #[allow(unused)]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[allow(unused)]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Corners (min, max) of the bounding box of all set positions
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Point, &T)> {
+        self.cells.iter()
+    }
+
+    #[allow(unused)]
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.values()
+    }
+
+    #[allow(unused)]
+    pub fn neighbors(&self, point: Point) -> impl Iterator<Item = Point> {
+        Octile::cardinal().map(move |direction| point + direction.point_delta())
+    }
+
+    #[allow(unused)]
+    pub fn neighbors_with_diagonal(&self, point: Point) -> impl Iterator<Item = Point> {
+        Octile::all_cases().map(move |direction| point + direction.point_delta())
+    }
+
+    /// Converts to a dense grid covering the bounding box, returning it along with the position
+    /// of its top-left corner, which is an empty grid at the origin when there are no points
+    #[allow(unused)]
+    pub fn to_grid(&self) -> (Grid<T>, Point)
+    where
+        T: Clone,
+    {
+        let Some((min, max)) = self.bounds else {
+            return (Grid::from_fn(0, 0, |_, _| unreachable!()), Point::origin());
+        };
+        let width = (max.x - min.x + 1) as usize;
+        let height = (max.y - min.y + 1) as usize;
+        let mut grid = Grid::from(vec![vec![self.default.clone(); width]; height]);
+        for (point, value) in self.cells.iter() {
+            grid.set(
+                (point.y - min.y) as usize,
+                (point.x - min.x) as usize,
+                value.clone(),
+            );
+        }
+        (grid, min)
+    }
+
+    /// Draws the bounding box of the grid, one line per row
+    pub fn render<F>(&self, f: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        let Some((min, max)) = self.bounds else {
+            return String::new();
+        };
+        (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| f(self.get(&Point::new(x, y))))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl std::fmt::Display for SparseGrid<char> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.render(|c| *c))
+    }
+}
+
+/// Graph whose nodes are identified by string labels, interned to dense ids in the order they
+/// are first seen, with data of type N on each node and weights of type E on each edge
+#[derive(Clone)]
+pub struct Graph<N, E> {
+    ids: HashMap<String, usize>,
+    labels: Vec<String>,
+    nodes: Vec<N>,
+    outgoing: Vec<Vec<(usize, E)>>,
+    incoming: Vec<Vec<usize>>,
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn new() -> Self {
+        Self {
+            ids: HashMap::new(),
+            labels: Vec::new(),
+            nodes: Vec::new(),
+            outgoing: Vec::new(),
+            incoming: Vec::new(),
+        }
+    }
+
+    /// Adds a node with the given data, replacing the data if the label already has a node
+    #[allow(unused)]
+    pub fn add_node(&mut self, label: &str, data: N) -> usize {
+        match self.ids.get(label) {
+            Some(&id) => {
+                self.nodes[id] = data;
+                id
+            }
+            None => self.push_node(label, data),
+        }
+    }
+
+    /// Id of the node with the given label, adding it with default data if it is new
+    pub fn intern(&mut self, label: &str) -> usize
+    where
+        N: Default,
+    {
+        match self.ids.get(label) {
+            Some(&id) => id,
+            None => self.push_node(label, N::default()),
+        }
+    }
+
+    fn push_node(&mut self, label: &str, data: N) -> usize {
+        let id = self.labels.len();
+        self.ids.insert(label.to_string(), id);
+        self.labels.push(label.to_string());
+        self.nodes.push(data);
+        self.outgoing.push(Vec::new());
+        self.incoming.push(Vec::new());
+        id
+    }
+
+    pub fn id(&self, label: &str) -> Option<usize> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn label(&self, id: usize) -> &str {
+        &self.labels[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    #[allow(unused)]
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Ids of every node, which run from 0 up to the number of nodes
+    pub fn ids(&self) -> Range<usize> {
+        0..self.len()
+    }
+
+    pub fn node(&self, id: usize) -> &N {
+        &self.nodes[id]
+    }
+
+    pub fn node_mut(&mut self, id: usize) -> &mut N {
+        &mut self.nodes[id]
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: E) {
+        self.outgoing[from].push((to, weight));
+        self.incoming[to].push(from);
+    }
+
+    /// Adds an edge in each direction between the two nodes
+    pub fn add_undirected_edge(&mut self, a: usize, b: usize, weight: E)
+    where
+        E: Clone,
+    {
+        self.add_edge(a, b, weight.clone());
+        self.add_edge(b, a, weight);
+    }
+
+    /// Nodes which the node has an edge to, along with the weight of each edge
+    pub fn edges(&self, id: usize) -> impl Iterator<Item = (usize, &E)> {
+        self.outgoing[id].iter().map(|(to, weight)| (*to, weight))
+    }
+
+    pub fn neighbors(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.outgoing[id].iter().map(|&(to, _)| to)
+    }
+
+    /// Nodes which have an edge to the node
+    pub fn incoming(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.incoming[id].iter().copied()
+    }
+
+    #[allow(unused)]
+    pub fn edge(&self, from: usize, to: usize) -> Option<&E> {
+        self.edges(from)
+            .find(|&(next, _)| next == to)
+            .map(|(_, weight)| weight)
+    }
+
+    pub fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.neighbors(from).any(|next| next == to)
+    }
+
+    /// Number of edges leaving the node, which for undirected graphs is the number of neighbors
+    #[allow(unused)]
+    pub fn degree(&self, id: usize) -> usize {
+        self.outgoing[id].len()
+    }
+
+    #[allow(unused)]
+    pub fn in_degree(&self, id: usize) -> usize {
+        self.incoming[id].len()
+    }
+}
+
+impl<N, E> Graph<N, E>
+where
+    N: Default,
+    E: Clone + Default,
+{
+    /// Parses one undirected edge per line, given as two labels joined by the separator e.g. "a-b"
+    pub fn parse_edges(input: &str, separator: &str) -> Result<Self, Error> {
+        let mut graph = Self::new();
+        for line in input.lines() {
+            let (a, b) = utils::split(line, separator)
+                .ok_or_else(|| Error::msg(format!("missing separator in edge {:?}", line)))?;
+            let a = graph.intern(a);
+            let b = graph.intern(b);
+            graph.add_undirected_edge(a, b, E::default());
+        }
+        Ok(graph)
+    }
+
+    /// Parses one node per line followed by the nodes it has directed edges to, e.g. "a: b c"
+    pub fn parse_adjacency(input: &str) -> Result<Self, Error> {
+        let mut graph = Self::new();
+        for line in input.lines() {
+            let (from, to) = utils::split(line, ":")
+                .ok_or_else(|| Error::msg(format!("missing colon in node {:?}", line)))?;
+            let from = graph.intern(from);
+            for to in to.split_whitespace() {
+                let to = graph.intern(to);
+                graph.add_edge(from, to, E::default());
+            }
+        }
+        Ok(graph)
+    }
+}
+
+/// Partition of the elements 0..len into disjoint sets, using union by rank and path compression
+#[derive(Clone, Debug)]
+pub struct DisjointSet {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    count: usize,
+}
+
+impl DisjointSet {
+    /// Creates a partition where every element is in a set of its own
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+            sizes: vec![1; len],
+            count: len,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Number of disjoint sets
+    #[allow(unused)]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Representative element of the set containing x
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        // point everything on the way straight at the root
+        let mut current = x;
+        while self.parents[current] != root {
+            current = std::mem::replace(&mut self.parents[current], root);
+        }
+        root
+    }
+
+    /// Merges the sets containing a and b, returning false if they were already the same set
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        // attach the shallower tree under the deeper one
+        let (root, child) = if self.ranks[a] < self.ranks[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        if self.ranks[root] == self.ranks[child] {
+            self.ranks[root] += 1;
+        }
+        self.count -= 1;
+        true
+    }
+
+    #[allow(unused)]
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the set containing x
+    #[allow(unused)]
+    pub fn set_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.sizes[root]
+    }
+
+    /// Elements of each set, ordered by their smallest element
+    pub fn sets(&mut self) -> Vec<Vec<usize>> {
+        let mut positions = HashMap::new();
+        let mut sets = Vec::<Vec<usize>>::with_capacity(self.count);
+        for x in 0..self.len() {
+            let root = self.find(x);
+            let position = *positions.entry(root).or_insert_with(|| {
+                sets.push(Vec::new());
+                sets.len() - 1
+            });
+            sets[position].push(x);
+        }
+        sets
+    }
+
+    /// Joins the sets at the ends of each edge in turn, yielding the edges which joined two sets
+    /// and stopping once everything is in one set. Given edges sorted by weight, the edges
+    /// yielded form a minimum spanning forest as in Kruskal's algorithm.
+    pub fn spanning_edges<I, W>(&mut self, edges: I) -> impl Iterator<Item = (usize, usize, W)>
+    where
+        I: IntoIterator<Item = (usize, usize, W)>,
+    {
+        let mut edges = edges.into_iter();
+        std::iter::from_fn(move || {
+            while self.count > 1 {
+                let (a, b, weight) = edges.next()?;
+                if self.union(a, b) {
+                    return Some((a, b, weight));
+                }
+            }
+            None
+        })
+    }
+}
+
+/// Counts which a Counter can keep, where the default value is zero
+pub trait Count: Copy + Ord + Default + AddAssign + SubAssign + From<u8> + Sum {}
+
+impl<T> Count for T where T: Copy + Ord + Default + AddAssign + SubAssign + From<u8> + Sum {}
+
+/// Number of times each element has been seen, counted in usize unless another count type is
+/// needed for larger counts
+#[derive(Clone, Debug)]
+pub struct Counter<T, C = usize>
+where
+    T: Hash + Eq,
+    C: Count,
+{
+    counts: HashMap<T, C>,
+}
+
+impl<T, C> Counter<T, C>
+where
+    T: Hash + Eq,
+    C: Count,
+{
+    pub fn new() -> Self {
+        Self {
+            counts: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, element: T) -> C {
+        self.counts.get(&element).copied().unwrap_or_default()
+    }
+
+    pub fn add(&mut self, element: T) {
+        self.add_many(element, C::from(1));
+    }
+
+    pub fn add_many(&mut self, element: T, count: C) {
+        *self.counts.entry(element).or_default() += count;
+    }
+
+    pub fn extend<I>(&mut self, iter: I)
+    where
+        I: Iterator<Item = T>,
+    {
+        for item in iter {
+            self.add(item);
+        }
+    }
+
+    pub fn remove(&mut self, element: T) -> Option<C> {
+        self.counts.remove(&element)
+    }
+
+    /// Entry for the count of an element, to update it in place
+    #[allow(unused)]
+    pub fn entry(&mut self, element: T) -> hash_map::Entry<'_, T, C> {
+        self.counts.entry(element)
+    }
+
+    /// Adds the counts of another counter
+    pub fn merge(&mut self, other: Self) {
+        for (element, count) in other.counts {
+            self.add_many(element, count);
+        }
+    }
+
+    /// Subtracts the counts of another counter, removing any elements whose count reaches zero
+    #[allow(unused)]
+    pub fn subtract(&mut self, other: &Self)
+    where
+        T: Clone,
+    {
+        for (element, &count) in other.counts.iter() {
+            if let hash_map::Entry::Occupied(mut entry) = self.counts.entry(element.clone()) {
+                if *entry.get() <= count {
+                    entry.remove();
+                } else {
+                    *entry.get_mut() -= count;
+                }
+            }
+        }
+    }
+
+    /// The n elements with the highest counts, from most to least common
+    pub fn most_common(&self, n: usize) -> Vec<(&T, C)> {
+        let mut counts = self.iter().map(|(k, &v)| (k, v)).collect::<Vec<_>>();
+        let by_count = |a: &(&T, C), b: &(&T, C)| b.1.cmp(&a.1);
+        // partition out the n most common before sorting only those
+        if n < counts.len() {
+            counts.select_nth_unstable_by(n, by_count);
+            counts.truncate(n);
+        }
+        counts.sort_unstable_by(by_count);
+        counts
+    }
+
+    /// The lowest count of any element, or zero if there are none
+    pub fn min(&self) -> C {
+        self.counts.values().copied().min().unwrap_or_default()
     }
 
-    pub fn max(&self) -> usize {
-        let sorted = self.sorted().collect::<Vec<_>>();
-        sorted.first().map_or(0, |&(_, count)| count)
+    /// The highest count of any element, or zero if there are none
+    pub fn max(&self) -> C {
+        self.counts.values().copied().max().unwrap_or_default()
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = (&T, &usize)> {
+    /// The sum of the counts of every element
+    pub fn total(&self) -> C {
+        self.counts.values().copied().sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&T, &C)> {
         self.counts.iter()
     }
 }
 
-impl<T> FromIterator<T> for Counter<T>
+impl<T, C> Default for Counter<T, C>
+where
+    T: Hash + Eq,
+    C: Count,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, C> FromIterator<T> for Counter<T, C>
 where
     T: Hash + Eq,
+    C: Count,
 {
     fn from_iter<I>(iter: I) -> Self
     where
@@ -795,3 +2600,703 @@ where
         counter
     }
 }
+
+/// Indexing panics if the element has not been counted, whereas get returns zero
+impl<T, C> Index<&T> for Counter<T, C>
+where
+    T: Hash + Eq,
+    C: Count,
+{
+    type Output = C;
+
+    fn index(&self, element: &T) -> &Self::Output {
+        &self.counts[element]
+    }
+}
+
+impl<T, C> IntoIterator for Counter<T, C>
+where
+    T: Hash + Eq,
+    C: Count,
+{
+    type Item = (T, C);
+    type IntoIter = hash_map::IntoIter<T, C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+impl<'a, T, C> IntoIterator for &'a Counter<T, C>
+where
+    T: Hash + Eq,
+    C: Count,
+{
+    type Item = (&'a T, &'a C);
+    type IntoIter = hash_map::Iter<'a, T, C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    fn grid(rows: &str) -> Grid<char> {
+        Grid::from(rows.to_string())
+    }
+
+    fn collect<'a, I>(cells: I) -> String
+    where
+        I: Iterator<Item = &'a char>,
+    {
+        cells.collect()
+    }
+
+    #[test]
+    fn indexing() {
+        let mut g = grid("abc\ndef");
+        assert_eq!(g[(1, 2)], 'f');
+        g[(1, 2)] = 'z';
+        assert_eq!(g.get(1, 2), &'z');
+        assert_eq!(g.try_get(0, 1), Some(&'b'));
+        assert_eq!(g.try_get(2, 0), None);
+        assert_eq!(g.try_get(0, 3), None);
+        *g.try_get_mut(0, 0).unwrap() = 'y';
+        assert!(g.try_get_mut(5, 5).is_none());
+        assert_eq!(g.get_offset(1, 1, -1, 1), Some(&'c'));
+        assert_eq!(g.get_offset(1, 1, 1, 0), None);
+        assert_eq!(g.offset(0, 0, -1, 0), None);
+        assert_eq!(g.row(1), ['d', 'e', 'z']);
+        assert_eq!(
+            g.rows().collect::<Vec<_>>(),
+            vec![['y', 'b', 'c'], ['d', 'e', 'z']]
+        );
+        assert_eq!(collect(g.iter_row(0)), "ybc");
+        assert_eq!(collect(g.iter_col(1)), "be");
+        g.row_mut(0).copy_from_slice(&['p', 'q', 'r']);
+        assert_eq!(g.to_string(), "pqr\ndez\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "Grid::iter_col: column out of bounds")]
+    fn iter_col_out_of_bounds() {
+        // column 3 would otherwise start at the first cell of the next row
+        grid("abc\ndef").iter_col(3).count();
+    }
+
+    #[test]
+    fn transpose() {
+        assert_eq!(grid("abc\ndef").transpose().to_string(), "ad\nbe\ncf\n");
+    }
+
+    #[test]
+    fn rotate() {
+        let g = grid("abc\ndef");
+        assert_eq!(g.rotate_90_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(g.rotate_180().to_string(), "fed\ncba\n");
+        assert_eq!(g.rotate_90_counterclockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(
+            g.rotate_90_clockwise()
+                .rotate_90_counterclockwise()
+                .to_string(),
+            g.to_string()
+        );
+    }
+
+    #[test]
+    fn flip() {
+        let g = grid("abc\ndef");
+        assert_eq!(g.flip_horizontal().to_string(), "cba\nfed\n");
+        assert_eq!(g.flip_vertical().to_string(), "def\nabc\n");
+    }
+
+    #[test]
+    fn pad() {
+        let g = grid("ab\ncd").pad(1, '.');
+        assert_eq!((g.width, g.height), (4, 4));
+        assert_eq!(g.to_string(), "....\n.ab.\n.cd.\n....\n");
+    }
+
+    #[test]
+    fn sub_grid() {
+        let g = grid("abcd\nefgh\nijkl");
+        let sub = g.sub_grid(1, 1, 2, 2);
+        assert_eq!(*sub.get(0, 0), 'f');
+        assert_eq!(*sub.get(1, 1), 'k');
+        assert_eq!(sub.to_grid().to_string(), "fg\njk\n");
+        assert_eq!(sub.iter_grid().count(), 4);
+        // sub-grids are clipped to the grid
+        let clipped = g.sub_grid(2, 3, 5, 5);
+        assert_eq!((clipped.width, clipped.height), (1, 1));
+        assert_eq!(*clipped.get(0, 0), 'l');
+    }
+
+    #[test]
+    fn tile() {
+        let g = grid("ab\ncd");
+        let tiled = g.tile(2, 3, |row, col| match (row, col) {
+            (0, 0) => Transform::Identity,
+            (0, 1) => Transform::Rotate90,
+            (0, 2) => Transform::FlipHorizontal,
+            (1, 0) => Transform::Rotate180,
+            (1, 1) => Transform::Transpose,
+            _ => Transform::FlipVertical,
+        });
+        assert_eq!((tiled.width, tiled.height), (6, 4));
+        assert_eq!(tiled.to_string(), "abcaba\ncddbdc\ndcaccd\nbabdab\n");
+        assert_eq!(g.transform(Transform::Rotate270).to_string(), "bd\nac\n");
+    }
+
+    #[test]
+    #[should_panic]
+    fn tile_mismatched_sizes() {
+        grid("abc\ndef").tile(1, 2, |_, col| {
+            if col == 0 {
+                Transform::Identity
+            } else {
+                Transform::Rotate90
+            }
+        });
+    }
+
+    #[test]
+    fn widen() {
+        let g = grid("#O\n@.").widen(|c| match c {
+            'O' => ['[', ']'],
+            '@' => ['@', '.'],
+            c => [*c, *c],
+        });
+        assert_eq!(g.width, 4);
+        assert_eq!(g.to_string(), "##[]\n@...\n");
+    }
+
+    #[test]
+    fn diagonals() {
+        let g = grid("abc\ndef");
+        assert_eq!(collect(g.iter_diagonal(0, 0)), "ae");
+        assert_eq!(collect(g.iter_antidiagonal(0, 2)), "ce");
+        assert_eq!(
+            g.diagonals()
+                .map(|line| line.map(|position| g[position]).collect::<String>())
+                .collect::<Vec<_>>(),
+            ["d", "ae", "bf", "c"]
+        );
+        assert_eq!(
+            g.antidiagonals()
+                .map(|line| line.map(|position| g[position]).collect::<String>())
+                .collect::<Vec<_>>(),
+            ["a", "bd", "ce", "f"]
+        );
+        assert_eq!(
+            g.diagonals().nth(1).unwrap().collect::<Vec<_>>(),
+            [(0, 0), (1, 1)]
+        );
+    }
+
+    #[test]
+    fn bounded_topology() {
+        let g = grid("abc\ndef");
+        assert_eq!(g.neighbor(0, 0, Octile::North), None);
+        assert_eq!(g.neighbor(0, 0, Octile::East), Some((0, 1)));
+        assert_eq!(g.neighbors(0, 0).len(), 2);
+        assert_eq!(g.offset(1, 2, -1, -2), Some((0, 0)));
+        assert_eq!(g.offset(1, 2, 0, 1), None);
+    }
+
+    #[test]
+    fn toroidal_topology() {
+        let g = grid("abc\ndef").with_topology(Topology::Toroidal);
+        assert_eq!(g.neighbor(0, 0, Octile::North), Some((1, 0)));
+        assert_eq!(g.neighbor(0, 0, Octile::West), Some((0, 2)));
+        assert_eq!(g.neighbor(1, 2, Octile::SouthEast), Some((0, 0)));
+        assert_eq!(g.neighbors_with_diagonal(0, 0).len(), 8);
+        assert_eq!(g.offset(0, 0, -7, 10), Some((1, 1)));
+        // the topology is kept when mapping the grid
+        assert_eq!(
+            g.map(|c| c.to_ascii_uppercase()).offset(0, 0, -1, -1),
+            Some((1, 2))
+        );
+    }
+
+    #[test]
+    fn custom_topology() {
+        // walking off the top edge turns around and comes back down the mirrored column
+        let edge_map: EdgeMap = Arc::new(|(i, j), direction| match direction {
+            Octile::North => Some(((i, 2 - j), Octile::South)),
+            _ => None,
+        });
+        let g = grid("abc\ndef").with_topology(Topology::Custom(edge_map));
+        assert_eq!(g.step(0, 0, Octile::North), Some(((0, 2), Octile::South)));
+        assert_eq!(g.step(1, 1, Octile::North), Some(((0, 1), Octile::North)));
+        assert_eq!(g.step(0, 2, Octile::East), None);
+        assert_eq!(g.neighbor(0, 1, Octile::North), Some((0, 1)));
+    }
+
+    #[test]
+    fn parse() {
+        let g = "ab\r\ncd\r\n\n".parse::<Grid<char>>().unwrap();
+        assert_eq!((g.width, g.height), (2, 2));
+        assert_eq!(g.to_string(), "ab\ncd\n");
+        let digits = Grid::parse_with("12\n34", |c| c.to_digit(10)).unwrap();
+        assert_eq!(digits[(1, 0)], 3);
+        let from_str = Grid::<char>::try_from("xy").unwrap();
+        assert_eq!(from_str.to_string(), "xy\n");
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!("".parse::<Grid<char>>().err(), Some(GridParseError::Empty));
+        assert_eq!(
+            "\n\n".parse::<Grid<char>>().err(),
+            Some(GridParseError::Empty)
+        );
+        assert_eq!(
+            "abc\nde".parse::<Grid<char>>().err(),
+            Some(GridParseError::RaggedRow {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            Grid::parse_with("12\n3x", |c| c.to_digit(10)).err(),
+            Some(GridParseError::BadCell {
+                row: 1,
+                col: 1,
+                cell: 'x'
+            })
+        );
+    }
+
+    #[test]
+    fn parse_markers() {
+        let (g, markers) =
+            Grid::<char>::parse_with_markers("S.#\n.#E\nS..", &['S', 'E'], '.').unwrap();
+        assert_eq!(markers[&'S'], [(0, 0), (2, 0)]);
+        assert_eq!(markers[&'E'], [(1, 2)]);
+        assert_eq!(g.to_string(), "..#\n.#.\n...\n");
+    }
+
+    #[test]
+    fn from_empty_rows() {
+        let g = Grid::<u8>::from(Vec::new());
+        assert_eq!((g.width, g.height), (0, 0));
+        assert_eq!(g.iter_grid().count(), 0);
+        assert_eq!(g.rows().count(), 0);
+        let g = Grid::<u8>::from(vec![Vec::new(), Vec::new()]);
+        assert_eq!((g.width, g.height), (0, 2));
+        assert_eq!(g.iter_grid().count(), 0);
+    }
+
+    #[test]
+    fn sparse_grid() {
+        let mut sparse = SparseGrid::new('.');
+        assert_eq!(sparse.bounds(), None);
+        assert_eq!(sparse.render(|&c| c), "");
+        let (empty, top_left) = sparse.to_grid();
+        assert_eq!((empty.width, empty.height), (0, 0));
+        assert_eq!(top_left, Point::origin());
+        sparse.set(Point::new(2, -1), '#');
+        sparse.set(Point::new(-1, 1), '@');
+        sparse.set(Point::new(0, 0), '#');
+        assert_eq!(
+            sparse.bounds(),
+            Some((Point::new(-1, -1), Point::new(2, 1)))
+        );
+        assert_eq!(sparse.get(&Point::new(0, 0)), &'#');
+        assert_eq!(sparse.get(&Point::new(5, 5)), &'.');
+        assert_eq!(sparse.to_string(), "...#\n.#..\n@...\n");
+
+        // removing a point inside the bounding box leaves it alone, but one on its edge shrinks it
+        assert_eq!(sparse.remove(&Point::new(0, 0)), Some('#'));
+        assert_eq!(
+            sparse.bounds(),
+            Some((Point::new(-1, -1), Point::new(2, 1)))
+        );
+        assert_eq!(sparse.remove(&Point::new(0, 0)), None);
+        sparse.remove(&Point::new(2, -1));
+        assert_eq!(
+            sparse.bounds(),
+            Some((Point::new(-1, 1), Point::new(-1, 1)))
+        );
+        sparse.remove(&Point::new(-1, 1));
+        assert_eq!(sparse.bounds(), None);
+        assert!(sparse.is_empty());
+
+        // the dense grid covers the bounding box, so converting back only keeps the same cells
+        let dense = grid("....\n.#..\n..@.\n....");
+        let sparse = SparseGrid::from_grid(&dense, '.');
+        assert_eq!(sparse.len(), 2);
+        let (cropped, top_left) = sparse.to_grid();
+        assert_eq!(top_left, Point::new(1, 1));
+        assert_eq!(cropped.to_string(), "#.\n.@\n");
+        let round_trip = SparseGrid::from_grid(&cropped, '.');
+        let mut points = round_trip
+            .iter()
+            .map(|(&point, &c)| (point + top_left, c))
+            .collect::<Vec<_>>();
+        points.sort();
+        let mut expected = sparse.iter().map(|(&p, &c)| (p, c)).collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(points, expected);
+    }
+
+    #[test]
+    fn distances() {
+        let maze = Maze::from(String::from("..#\n.##\n..."));
+        let d = maze.distance_matrix((0, 0));
+        assert_eq!(d.get(2, 2), &Some(4));
+        assert_eq!(d.get(0, 2), &None);
+        assert_eq!(d.get(1, 1), &None);
+        // several sources, and steps which may only climb by one
+        let heights = Grid::from(vec![vec![0, 1, 3], vec![0, 2, 2]]);
+        let d = heights.distances([(0, 0), (1, 0)], |(i, j), (ii, jj)| {
+            heights.get(ii, jj) - heights.get(i, j) <= 1
+        });
+        assert_eq!(d.get(1, 2), &Some(3));
+        assert_eq!(d.get(0, 2), &Some(4));
+    }
+
+    #[test]
+    fn weighted_distances() {
+        let risks = Grid::from(vec![vec![1, 9, 1], vec![1, 1, 1]]);
+        let d = risks.weighted_distances([((0, 0), 0)], |_, (i, j)| Some(*risks.get(i, j)));
+        assert_eq!(d.get(0, 2), &Some(4));
+        assert_eq!(d.get(0, 1), &Some(9));
+        // a second source with a head start, and a cell which cannot be entered
+        let d = risks.weighted_distances([((0, 0), 5), ((1, 2), 0)], |_, (i, j)| {
+            (*risks.get(i, j) < 9).then_some(*risks.get(i, j))
+        });
+        assert_eq!(d.get(0, 0), &Some(3));
+        assert_eq!(d.get(0, 1), &None);
+        let cost = |_, (i, j)| Some(*risks.get(i, j));
+        assert_eq!(
+            risks.weighted_distance([((0, 0), 0)], (0, 2), cost),
+            Some(4)
+        );
+        assert_eq!(
+            risks.weighted_distance([((0, 0), 0)], (0, 0), cost),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn flood_fill() {
+        let g = Grid::from(vec![vec![1, 0, 0], vec![0, 1, 0], vec![0, 0, 1]]);
+        let four = g.flood_fill([(0, 0)], Connectivity::Four, |a, b| a == b);
+        assert_eq!(four, vec![(0, 0)]);
+        let mut eight = g.flood_fill([(0, 0)], Connectivity::Eight, |a, b| a == b);
+        eight.sort();
+        assert_eq!(eight, vec![(0, 0), (1, 1), (2, 2)]);
+        let zeros = g.flood_fill([(0, 1), (2, 0)], Connectivity::Four, |_, &b| b == 0);
+        assert_eq!(zeros.len(), 6);
+    }
+
+    #[test]
+    fn label_regions() {
+        let g = Grid::from(String::from("AAAA\nBBCD\nBBCC\nEEEC"));
+        let regions = g.label_regions(Connectivity::Four, |a, b| a == b);
+        assert_eq!(regions.regions.len(), 5);
+        assert_eq!(regions.labels.get(1, 2), regions.labels.get(3, 3));
+        let c = &regions.regions[*regions.labels.get(1, 2)];
+        assert_eq!((c.area, c.perimeter, c.sides), (4, 10, 8));
+        assert_eq!(c.bounds, ((1, 2), (3, 3)));
+        let price = |regions: &Regions| {
+            regions
+                .regions
+                .iter()
+                .map(|r| (r.area * r.perimeter, r.area * r.sides))
+                .fold((0, 0), |(a, b), (p, s)| (a + p, b + s))
+        };
+        assert_eq!(price(&regions), (140, 80));
+        // regions enclosing holes which only touch diagonally
+        let g = Grid::from(String::from(
+            "AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA",
+        ));
+        let regions = g.label_regions(Connectivity::Four, |a, b| a == b);
+        assert_eq!(price(&regions).1, 368);
+        let a = &regions.regions[*regions.labels.get(0, 0)];
+        assert_eq!((a.area, a.sides), (28, 12));
+    }
+
+    #[test]
+    fn graph_parse() {
+        let g = Graph::<(), ()>::parse_edges("start-A\nA-b\nb-end\nA-end", "-").unwrap();
+        assert_eq!(g.len(), 4);
+        let a = g.id("A").unwrap();
+        assert_eq!(g.label(a), "A");
+        assert_eq!(g.degree(a), 3);
+        assert!(g.has_edge(g.id("end").unwrap(), a));
+        assert!(Graph::<(), ()>::parse_edges("a=b", "-").is_err());
+
+        let g = Graph::<(), ()>::parse_adjacency("you: a b\na: out\nb: out").unwrap();
+        let out = g.id("out").unwrap();
+        assert_eq!((g.degree(out), g.in_degree(out)), (0, 2));
+        let you = g.id("you").unwrap();
+        assert_eq!(
+            g.neighbors(you).map(|n| g.label(n)).collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        assert!(Graph::<(), ()>::parse_adjacency("a b").is_err());
+    }
+
+    #[test]
+    fn graph_weights() {
+        let mut g = Graph::<u32, u8>::new();
+        let a = g.add_node("a", 7);
+        let b = g.intern("b");
+        g.add_edge(a, b, 3);
+        g.add_undirected_edge(b, a, 5);
+        assert_eq!((*g.node(a), *g.node(b)), (7, 0));
+        assert_eq!(g.edge(a, b), Some(&3));
+        assert_eq!(g.edge(b, a), Some(&5));
+        assert_eq!(g.incoming(a).collect::<Vec<_>>(), [b]);
+        assert_eq!(g.edges(a).count(), 2);
+        assert_eq!(g.add_node("a", 1), a);
+        assert_eq!(*g.node(a), 1);
+    }
+
+    #[test]
+    fn disjoint_set() {
+        let mut sets = DisjointSet::new(6);
+        assert_eq!(sets.count(), 6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.same_set(0, 3));
+        assert!(!sets.same_set(0, 4));
+        assert_eq!(
+            (sets.count(), sets.set_size(2), sets.set_size(5)),
+            (3, 4, 1)
+        );
+        assert_eq!(sets.sets(), vec![vec![0, 1, 2, 3], vec![4], vec![5]]);
+
+        // only edges joining two sets are kept, and nothing is read once all are joined
+        let edges = [(0, 4, 'a'), (1, 4, 'b'), (4, 5, 'c'), (5, 0, 'd')];
+        let spanning = sets.spanning_edges(edges).collect::<Vec<_>>();
+        assert_eq!(spanning, vec![(0, 4, 'a'), (4, 5, 'c')]);
+        assert_eq!(sets.count(), 1);
+    }
+
+    #[test]
+    fn interval_set() {
+        let mut set = [0..3, 10..12, 2..5, 5..6]
+            .into_iter()
+            .collect::<IntervalSet<i64>>();
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..6, 10..12]);
+        assert_eq!(set.len(), 8);
+        set.insert(6..10);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..12]);
+        set.remove(3..5);
+        set.remove(20..30);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..3, 5..12]);
+        assert!(set.contains(&0) && set.contains(&11));
+        assert!(!set.contains(&3) && !set.contains(&12) && !set.contains(&-1));
+
+        let other = [2..6, 8..9, 11..15]
+            .into_iter()
+            .collect::<IntervalSet<i64>>();
+        let ranges = |set: IntervalSet<i64>| set.iter().collect::<Vec<_>>();
+        assert_eq!(ranges(set.union(&other)), vec![0..15]);
+        assert_eq!(
+            ranges(set.intersection(&other)),
+            vec![2..3, 5..6, 8..9, 11..12]
+        );
+        assert_eq!(ranges(set.difference(&other)), vec![0..2, 6..8, 9..11]);
+        assert_eq!(ranges(other.difference(&set)), vec![3..5, 12..15]);
+        assert!(set.difference(&set).is_empty());
+    }
+
+    #[test]
+    fn interval_map() {
+        let mut map = IntervalMap::new();
+        map.insert(10..20, 100);
+        map.insert(30..35, -30);
+        assert_eq!(
+            (map.get(5), map.get(10), map.get(19), map.get(20)),
+            (5, 110, 119, 20)
+        );
+        assert_eq!(map.get(32), 2);
+
+        let set = [0..12, 18..32].into_iter().collect::<IntervalSet<i64>>();
+        let mapped = map.map_set(&set).iter().collect::<Vec<_>>();
+        assert_eq!(mapped, vec![0..10, 20..30, 110..112, 118..120]);
+    }
+
+    #[test]
+    fn point_n() {
+        let a = Point3D::try_from("1,-2,3").unwrap();
+        let b = Point3D::new([4, 2, 3]);
+        assert_eq!(a.to_string(), "(1,-2,3)");
+        assert!(Point3D::try_from("1,2").is_err() && Point3D::try_from("1,2,3,4").is_err());
+        assert_eq!(b - a, Point3D::new([3, 4, 0]));
+        assert_eq!(a + b * 2, Point3D::new([9, 2, 9]));
+        assert_eq!(Point3D::manhattan_distance(a, b), 7);
+        assert_eq!(Point3D::chebyshev_distance(a, b), 4);
+        assert_eq!(Point3D::squared_distance(a, b), 25);
+        assert_eq!(Point2D::from(Point::new(1, 2)).y(), 2);
+
+        let origin = Point4D::origin();
+        let axis = origin.axis_neighbors().collect::<HashSet<_>>();
+        assert_eq!(axis.len(), 8);
+        assert!(
+            axis.iter()
+                .all(|&p| Point4D::manhattan_distance(origin, p) == 1)
+        );
+        let moore = origin.moore_neighbors().collect::<HashSet<_>>();
+        assert_eq!(moore.len(), 80);
+        assert!(
+            moore
+                .iter()
+                .all(|&p| Point4D::chebyshev_distance(origin, p) == 1)
+        );
+    }
+
+    #[test]
+    fn line_intersection() {
+        let line = |x0, y0, x1, y1| Line::new(Point::new(x0, y0), Point::new(x1, y1));
+        let point = |x, y| Intersection::Point(Rational::new(x, 1), Rational::new(y, 1));
+
+        // crossing, including with slopes which are not whole numbers
+        assert_eq!(
+            Line::intersection(&line(0, 0, 4, 4), &line(0, 4, 4, 0)),
+            point(2, 2)
+        );
+        let half = Intersection::Point(Rational::new(3, 2), Rational::new(1, 1));
+        assert_eq!(
+            Line::intersection(&line(0, 0, 3, 2), &line(0, 2, 3, 0)),
+            half
+        );
+        assert_eq!(
+            Line::intersection(&line(2, -3, 2, 3), &line(0, 0, 6, 3)),
+            point(2, 1)
+        );
+        // touching at an endpoint, and missing
+        assert_eq!(
+            Line::intersection(&line(0, 0, 2, 2), &line(2, 2, 5, 0)),
+            point(2, 2)
+        );
+        assert_eq!(
+            Line::intersection(&line(0, 0, 2, 2), &line(3, 3, 5, 0)),
+            Intersection::Disjoint
+        );
+        assert_eq!(
+            Line::intersection(&line(0, 0, 2, 0), &line(0, 1, 2, 1)),
+            Intersection::Disjoint
+        );
+        // collinear
+        let overlap = Line::intersection(&line(6, 3, 0, 0), &line(2, 1, 10, 5));
+        assert_eq!(overlap, Intersection::Overlap(line(2, 1, 6, 3)));
+        assert_eq!(
+            Line::intersection(&line(0, 0, 2, 0), &line(2, 0, 3, 0)),
+            point(2, 0)
+        );
+        assert_eq!(
+            Line::intersection(&line(0, 0, 1, 0), &line(2, 0, 3, 0)),
+            Intersection::Disjoint
+        );
+        assert_eq!(point(1, 2).lattice_point(), Some(Point::new(1, 2)));
+
+        let points = line(6, 3, 0, 0).points().collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            vec![
+                Point::new(6, 3),
+                Point::new(4, 2),
+                Point::new(2, 1),
+                Point::new(0, 0)
+            ]
+        );
+        assert_eq!(line(1, 1, 1, 1).points().count(), 1);
+        assert!(line(0, 0, 6, 3).contains_point(&Point::new(4, 2)));
+        assert!(!line(0, 0, 6, 3).contains_point(&Point::new(3, 1)));
+    }
+
+    #[test]
+    fn directions() {
+        let parse = |s: &str| {
+            s.chars()
+                .map(|c| Octile::try_from(c).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(parse("^>v<"), parse("URDL"));
+        assert_eq!(parse("^>v<"), parse("NESW"));
+        assert!(Octile::try_from('x').is_err());
+
+        assert_eq!(Octile::North.rotate(45), Octile::NorthEast);
+        assert_eq!(Octile::North.rotate(-135), Octile::SouthWest);
+        assert_eq!(Octile::West.rotate(720), Octile::West);
+        assert_eq!(Octile::SouthEast.opposite(), Octile::NorthWest);
+        assert_eq!(Octile::East.turn_90_counterclockwise(), Octile::North);
+        assert_eq!(Octile::East.angle_to(&Octile::NorthEast), 315);
+        assert_eq!(Octile::NorthEast.angle_to(&Octile::East), 45);
+        for direction in Octile::all_cases() {
+            let delta = direction.opposite().point_delta() + direction.point_delta();
+            assert_eq!(delta, Point::origin());
+        }
+
+        assert_eq!(Cardinal::try_from('L').unwrap(), Cardinal::West);
+        assert_eq!(Cardinal::North.rotate(-90), Cardinal::West);
+        assert_eq!(Cardinal::South.angle_to(&Cardinal::East), 270);
+        assert!(Cardinal::try_from(Octile::NorthWest).is_err());
+        assert_eq!(Cardinal::West.point_delta(), Point::new(-1, 0));
+
+        // a step in each hex direction around a cell returns to it, and each is 60 degrees apart
+        let around = HexDirection::all_cases().fold(Point::origin(), |p, d| p + d.point_delta());
+        assert_eq!(around, Point::origin());
+        assert_eq!(HexDirection::East.rotate(-60), HexDirection::NorthEast);
+        assert_eq!(
+            HexDirection::try_from("sw").unwrap().opposite(),
+            HexDirection::NorthEast
+        );
+
+        for direction in Direction3D::all_cases() {
+            let delta = direction.point_delta();
+            assert_eq!(
+                delta + direction.opposite().point_delta(),
+                Point3D::origin()
+            );
+            assert_eq!(Point3D::manhattan_distance(delta, Point3D::origin()), 1);
+        }
+    }
+
+    #[test]
+    fn counter() {
+        let mut counter = "abracadabra".chars().collect::<Counter<char>>();
+        assert_eq!(counter.get('a'), 5);
+        assert_eq!(counter.get('z'), 0);
+        assert_eq!(counter[&'b'], 2);
+        assert_eq!(counter.total(), 11);
+        assert_eq!((counter.min(), counter.max()), (1, 5));
+        assert_eq!(counter.most_common(1), vec![(&'a', 5)]);
+        let top_counts = counter.most_common(3).into_iter().map(|(_, count)| count);
+        assert_eq!(top_counts.collect::<Vec<_>>(), vec![5, 2, 2]);
+        assert_eq!(counter.most_common(10).len(), 5);
+        *counter.entry('z').or_default() += 3;
+        assert_eq!(counter.get('z'), 3);
+
+        let other = "bbaz".chars().collect::<Counter<char>>();
+        counter.subtract(&other);
+        assert_eq!(counter.get('a'), 4);
+        assert_eq!(counter.get('b'), 0);
+        assert_eq!(counter.get('z'), 2);
+        counter.merge(other);
+        assert_eq!(counter.get('b'), 2);
+        assert_eq!(counter.total(), 14);
+
+        // large counts need a wider count type
+        let mut large = Counter::<u8, u128>::new();
+        large.add_many(0, u64::MAX as u128);
+        large.add(0);
+        assert_eq!(large.max(), 1 << 64);
+        let mut pairs = large.into_iter().collect::<Vec<_>>();
+        pairs.sort();
+        assert_eq!(pairs, vec![(0, 1 << 64)]);
+    }
+}
@@ -0,0 +1,86 @@
+/*
+** src/visualize.rs
+*/
+
+use crate::types::{Grid, Point};
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static STARTED: AtomicBool = AtomicBool::new(false);
+static FPS: AtomicU32 = AtomicU32::new(30);
+
+/// Turns on frame rendering for the remainder of the run
+pub fn enable(fps: u32) {
+    FPS.store(fps.max(1), Ordering::Relaxed);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Renders a frame of a simulation in the terminal. The frame is only built if visualization is
+/// enabled, so this is free to call from solvers during normal runs.
+pub fn frame<F>(build: F)
+where
+    F: FnOnce() -> Grid<char>,
+{
+    if !is_enabled() {
+        return;
+    }
+
+    let grid = build();
+    let mut buffer = String::with_capacity((grid.width + 4) * grid.height + 16);
+    if !STARTED.swap(true, Ordering::Relaxed) {
+        // hide the cursor and clear the screen before the first frame
+        buffer.push_str("\x1b[?25l\x1b[2J");
+    }
+    // move the cursor home and draw over the previous frame, clearing leftovers from it
+    buffer.push_str("\x1b[H");
+    for i in 0..grid.height {
+        buffer.extend(grid.iter_row(i));
+        buffer.push_str("\x1b[K\n");
+    }
+    buffer.push_str("\x1b[J");
+
+    let mut stdout = io::stdout().lock();
+    let _ = stdout.write_all(buffer.as_bytes());
+    let _ = stdout.flush();
+    thread::sleep(Duration::from_secs_f64(
+        1.0 / FPS.load(Ordering::Relaxed) as f64,
+    ));
+}
+
+/// Restores the terminal after the final frame of a simulation
+pub fn finish() {
+    if STARTED.swap(false, Ordering::Relaxed) {
+        let mut stdout = io::stdout().lock();
+        let _ = stdout.write_all(b"\x1b[?25h");
+        let _ = stdout.flush();
+    }
+}
+
+/// Builds a frame from a set of marked points on an unbounded plane, where x is the column and y
+/// is the row; the frame covers the bounding box of the points
+pub fn points_frame<I>(points: I, background: char) -> Grid<char>
+where
+    I: IntoIterator<Item = (Point, char)>,
+{
+    let points = points.into_iter().collect::<Vec<_>>();
+    let x_min = points.iter().map(|(p, _)| p.x).min().unwrap_or(0);
+    let x_max = points.iter().map(|(p, _)| p.x).max().unwrap_or(0);
+    let y_min = points.iter().map(|(p, _)| p.y).min().unwrap_or(0);
+    let y_max = points.iter().map(|(p, _)| p.y).max().unwrap_or(0);
+
+    let width = (x_max - x_min + 1) as usize;
+    let height = (y_max - y_min + 1) as usize;
+    let mut grid = Grid::from(vec![vec![background; width]; height]);
+    for (point, c) in points {
+        grid.set((point.y - y_min) as usize, (point.x - x_min) as usize, c);
+    }
+    grid
+}